@@ -0,0 +1,153 @@
+use win64::{
+  Rect,
+  prelude::*,
+};
+
+/// What `NcHitTest` returns over a maximize button, `HTMAXBUTTON`.
+const HT_MAX_BUTTON: usize = 9;
+const CAPTION_HEIGHT: f64 = 32.0;
+const BUTTON_WIDTH: f64 = 46.0;
+
+struct State {
+  decorations: Decorations,
+  /// Set by `--click-maximize`, to check that the title bar stays custom once the window is maximized.
+  check_maximized: bool,
+}
+
+impl State {
+  pub fn new(check_maximized: bool) -> Self {
+    Self { decorations: Decorations::new(CAPTION_HEIGHT), check_maximized }
+  }
+
+  fn paint(&self, window: &Window) {
+    let Ok(client) = window.get_client_rect() else {
+      return;
+    };
+    let caption_height = (CAPTION_HEIGHT * window.scale_factor()).round() as i32;
+    window.begin_paint(|hdc, _| {
+      let caption = Brush::solid((32, 32, 32));
+      hdc.fill_rect(&Rect { bottom: caption_height, ..client }, &caption);
+      caption.delete();
+
+      for (button, rect) in caption_buttons(window) {
+        let color = match (button, self.decorations.pressed(), self.decorations.hovered()) {
+          (CaptionButton::Close, Some(CaptionButton::Close), _) => (140, 20, 20),
+          (CaptionButton::Close, _, Some(CaptionButton::Close)) => (196, 43, 28),
+          (_, Some(pressed), _) if pressed == button => (80, 80, 80),
+          (_, _, Some(hovered)) if hovered == button => (60, 60, 60),
+          _ => continue,
+        };
+        let brush = Brush::solid(color);
+        hdc.fill_rect(&rect, &brush);
+        brush.delete();
+      }
+    });
+  }
+
+  /// Quits once the window is maximized, with an error if the client area doesn't start at the top of the
+  /// work area, i.e. the standard title bar came back.
+  fn check_maximized(&self, window: &Window) {
+    if !self.check_maximized || !window.is_maximized() {
+      return;
+    }
+    let (Ok(work_area), Ok(client_top)) =
+      (window.monitor().work_area(), window.client_to_screen(PhysicalPosition::new(0, 0)))
+    else {
+      return;
+    };
+    if client_top.y != work_area.top {
+      eprintln!("the standard title bar came back after maximizing");
+      std::process::exit(1);
+    }
+    println!("maximized with the custom title bar");
+    window.quit();
+  }
+}
+
+impl WindowProcedure for State {
+  fn on_message(&mut self, window: &Window, message: &Message) -> Option<LResult> {
+    if let Some(result) = self.decorations.on_message(window, message) {
+      return Some(result);
+    }
+
+    match message {
+      Message::Create(_) => {
+        let _ = window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(is_os_dark_mode()));
+      },
+      Message::Size(_) => {
+        self.decorations.set_caption_buttons(caption_buttons(window));
+        self.check_maximized(window);
+      },
+      Message::Destroy => {
+        window.quit();
+      },
+      Message::Paint => {
+        self.paint(window);
+      },
+      _ => (),
+    }
+
+    None
+  }
+}
+
+/// The caption buttons along the right of the caption strip, for the window's size and DPI.
+fn caption_buttons(window: &Window) -> Vec<(CaptionButton, Rect)> {
+  let Ok(client) = window.get_client_rect() else {
+    return Vec::new();
+  };
+  let scale = window.scale_factor();
+  let height = (CAPTION_HEIGHT * scale).round() as i32;
+  let width = (BUTTON_WIDTH * scale).round() as i32;
+  [CaptionButton::Close, CaptionButton::Maximize, CaptionButton::Minimize]
+    .into_iter()
+    .zip(0..)
+    .map(|(button, index)| {
+      let right = client.right - width * index;
+      (button, Rect { left: right - width, top: 0, right, bottom: height })
+    })
+    .collect()
+}
+
+/// Clicks the maximize button the way the mouse would, by posting the messages for it.
+fn click_maximize(window: &Window) -> win64::Result<()> {
+  let Some((_, button)) =
+    caption_buttons(window).into_iter().find(|(button, _)| *button == CaptionButton::Maximize)
+  else {
+    return Ok(());
+  };
+  let center = window.client_to_screen(PhysicalPosition::new(
+    (button.left + button.right) / 2,
+    (button.top + button.bottom) / 2,
+  ))?;
+  let position = LParam(((center.y as u16 as isize) << 16) | center.x as u16 as isize);
+  window.post_message(Message::new(MessageId::NcLButtonDown, WParam(HT_MAX_BUTTON), position))?;
+  window.post_message(Message::new(MessageId::NcLButtonUp, WParam(HT_MAX_BUTTON), position))
+}
+
+fn main() -> win64::Result<()> {
+  let check_maximized = std::env::args().any(|arg| arg == "--click-maximize");
+
+  let class = WindowClass::builder()
+    .with_name("Decorations Class")
+    .with_background_brush(Brush::color_window())
+    .register()?;
+
+  let hwnd = class
+    .create_window()
+    .with_procedure(State::new(check_maximized))
+    .with_name("Decorations")
+    .with_style(WindowStyle::OverlappedWindow)
+    .with_size(Some(PhysicalSize::new(800, 500)))
+    .create()?;
+
+  hwnd.show_window(CmdShow::ShowDefault);
+
+  if check_maximized {
+    click_maximize(&hwnd)?;
+  }
+
+  MessageLoop::new().with_queue(MessageLoopQueue::Thread).with_filter(None).run();
+
+  Ok(())
+}
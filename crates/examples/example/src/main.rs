@@ -16,11 +16,11 @@ impl WindowProcedure for State {
     match message {
       Message::Create(_) => {
         // message.use_dark_mode(window, is_os_dark_mode());
-        window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(is_os_dark_mode()));
+        let _ = window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(is_os_dark_mode()));
         // window.dwm_set_window_attribute(DwmWindowAttribute::SystemBackdropType(SystemBackdropType::TransientWindow));
       },
       Message::SettingChange(_) => {
-        window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(is_os_dark_mode()));
+        let _ = window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(is_os_dark_mode()));
       },
       Message::Destroy => {
         window.quit();
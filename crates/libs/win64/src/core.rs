@@ -3,9 +3,6 @@ pub use handle::*;
 
 pub mod point;
 pub use point::*;
-
-pub mod rect;
-pub use rect::*;
 use {
   crate::user::CmdShow,
   libloading::{
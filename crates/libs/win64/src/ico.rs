@@ -0,0 +1,299 @@
+//!
+//! Reading the directory of `.ico` files.
+//!
+//! An `.ico` file is a list of images of different sizes, each stored either as a PNG or as the body
+//! of a BMP. Only the directory and the image headers are read here; the images themselves are handed
+//! to Windows as is.
+//!
+
+use std::fmt::{
+  self,
+  Display,
+};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const HEADER_LEN: usize = 6;
+const ENTRY_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParseIcoError {
+  /// The file ends before the end of the directory.
+  TooShort,
+  /// The reserved field isn't zero, or the file is a cursor rather than an icon.
+  InvalidHeader,
+  /// The file has no images.
+  Empty,
+  /// The image at this index lies outside of the file, or its header is cut off.
+  InvalidEntry(usize),
+}
+
+impl Display for ParseIcoError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::TooShort => write!(f, "file is too short to hold an icon directory"),
+      Self::InvalidHeader => write!(f, "not an icon file"),
+      Self::Empty => write!(f, "icon file has no images"),
+      Self::InvalidEntry(index) => write!(f, "image {index}: out of bounds or truncated"),
+    }
+  }
+}
+
+impl std::error::Error for ParseIcoError {}
+
+/// One image in an `.ico` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IcoEntry<'a> {
+  pub width: u32,
+  pub height: u32,
+  /// Bits per pixel, e.g. 32 for images with an alpha channel.
+  pub bit_depth: u16,
+  pub is_png: bool,
+  /// The image as stored in the file, which is what `CreateIconFromResourceEx` expects.
+  pub data: &'a [u8],
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+  u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+  u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+impl<'a> IcoEntry<'a> {
+  /// Reads the size and depth from the image itself, since the directory can't describe sizes over 255
+  /// and often leaves the depth out.
+  fn parse(data: &'a [u8]) -> Option<Self> {
+    match data.starts_with(PNG_SIGNATURE) {
+      true => {
+        // The IHDR chunk comes first: length, type, width, height, bit depth, color type.
+        let ihdr = data.get(16..26)?;
+        let channels = match ihdr[9] {
+          0 | 3 => 1,
+          4 => 2,
+          2 => 3,
+          _ => 4,
+        };
+        Some(Self {
+          width: u32::from_be_bytes(ihdr[0..4].try_into().unwrap()),
+          height: u32::from_be_bytes(ihdr[4..8].try_into().unwrap()),
+          bit_depth: ihdr[8] as u16 * channels,
+          is_png: true,
+          data,
+        })
+      },
+      false => {
+        // BITMAPINFOHEADER, where the height covers both the color and the mask rows.
+        let header = data.get(0..16)?;
+        Some(Self {
+          width: read_u32(header, 4),
+          height: read_u32(header, 8) / 2,
+          bit_depth: read_u16(header, 14),
+          is_png: false,
+          data,
+        })
+      },
+    }
+  }
+}
+
+/// The images of an `.ico` file, borrowed from the file's bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IcoFile<'a> {
+  entries: Vec<IcoEntry<'a>>,
+}
+
+impl<'a> IcoFile<'a> {
+  pub fn parse(bytes: &'a [u8]) -> std::result::Result<Self, ParseIcoError> {
+    if bytes.len() < HEADER_LEN {
+      return Err(ParseIcoError::TooShort);
+    }
+    if read_u16(bytes, 0) != 0 || read_u16(bytes, 2) != 1 {
+      return Err(ParseIcoError::InvalidHeader);
+    }
+    let count = read_u16(bytes, 4) as usize;
+    if count == 0 {
+      return Err(ParseIcoError::Empty);
+    }
+    if bytes.len() < HEADER_LEN + count * ENTRY_LEN {
+      return Err(ParseIcoError::TooShort);
+    }
+
+    let entries = (0..count)
+      .map(|index| {
+        let entry = HEADER_LEN + index * ENTRY_LEN;
+        let len = read_u32(bytes, entry + 8) as usize;
+        let offset = read_u32(bytes, entry + 12) as usize;
+        offset
+          .checked_add(len)
+          .and_then(|end| bytes.get(offset..end))
+          .and_then(IcoEntry::parse)
+          .ok_or(ParseIcoError::InvalidEntry(index))
+      })
+      .collect::<std::result::Result<_, _>>()?;
+
+    Ok(Self { entries })
+  }
+
+  pub fn entries(&self) -> &[IcoEntry<'a>] {
+    &self.entries
+  }
+
+  /// The image that scales best to `size` pixels: the smallest one at least that big, or the biggest
+  /// one if none are. Deeper images win ties.
+  pub fn best_for_size(&self, size: u32) -> &IcoEntry<'a> {
+    let extent = |entry: &IcoEntry| entry.width.max(entry.height);
+    self
+      .entries
+      .iter()
+      .filter(|entry| extent(entry) >= size)
+      .min_by_key(|entry| (extent(entry), std::cmp::Reverse(entry.bit_depth)))
+      .or_else(|| self.entries.iter().max_by_key(|entry| (extent(entry), entry.bit_depth)))
+      .unwrap()
+  }
+
+  /// Like [`IcoFile::best_for_size`], with `size` given at 96 DPI and scaled to `dpi`.
+  pub fn best_for_dpi(&self, size: u32, dpi: u32) -> &IcoEntry<'a> {
+    self.best_for_size((size * dpi).div_ceil(96))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// The start of a PNG up to the end of its IHDR chunk.
+  fn png(width: u32, height: u32, bit_depth: u8, color_type: u8) -> Vec<u8> {
+    let mut data = PNG_SIGNATURE.to_vec();
+    data.extend(13u32.to_be_bytes());
+    data.extend(b"IHDR");
+    data.extend(width.to_be_bytes());
+    data.extend(height.to_be_bytes());
+    data.extend([bit_depth, color_type, 0, 0, 0]);
+    data
+  }
+
+  /// A `BITMAPINFOHEADER`, with the height doubled to cover the mask.
+  fn dib(width: u32, height: u32, bit_depth: u16) -> Vec<u8> {
+    let mut data = 40u32.to_le_bytes().to_vec();
+    data.extend(width.to_le_bytes());
+    data.extend((height * 2).to_le_bytes());
+    data.extend(1u16.to_le_bytes());
+    data.extend(bit_depth.to_le_bytes());
+    data.resize(40, 0);
+    data
+  }
+
+  /// An icon file holding `images`, with the directory sizes truncated to a byte the way the format
+  /// stores them, so 256 becomes 0.
+  fn ico(images: &[(u32, u32, &[u8])]) -> Vec<u8> {
+    let mut bytes =
+      [0u16, 1, images.len() as u16].iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+    let mut offset = HEADER_LEN + images.len() * ENTRY_LEN;
+    for (width, height, data) in images {
+      bytes.extend([*width as u8, *height as u8, 0, 0]);
+      bytes.extend(1u16.to_le_bytes());
+      bytes.extend(0u16.to_le_bytes());
+      bytes.extend((data.len() as u32).to_le_bytes());
+      bytes.extend((offset as u32).to_le_bytes());
+      offset += data.len();
+    }
+    for (_, _, data) in images {
+      bytes.extend(*data);
+    }
+    bytes
+  }
+
+  #[test]
+  fn reads_png_and_dib_entries() {
+    let (small, large) = (dib(16, 16, 32), png(48, 48, 8, 6));
+    let bytes = ico(&[(16, 16, &small), (48, 48, &large)]);
+    let file = IcoFile::parse(&bytes).unwrap();
+
+    let [small_entry, large_entry] = file.entries() else { panic!("expected two entries") };
+    assert_eq!(*small_entry, IcoEntry { width: 16, height: 16, bit_depth: 32, is_png: false, data: &small });
+    assert_eq!(*large_entry, IcoEntry { width: 48, height: 48, bit_depth: 32, is_png: true, data: &large });
+  }
+
+  #[test]
+  fn png_depth_counts_every_channel() {
+    for (color_type, bit_depth) in [(0, 8), (2, 24), (3, 8), (4, 16), (6, 32)] {
+      let data = png(32, 32, 8, color_type);
+      let bytes = ico(&[(32, 32, &data)]);
+      assert_eq!(
+        IcoFile::parse(&bytes).unwrap().entries()[0].bit_depth,
+        bit_depth,
+        "color type {color_type}"
+      );
+    }
+  }
+
+  #[test]
+  fn sizes_of_256_come_from_the_image() {
+    // The directory stores 256 as 0, so the size is read from the image instead.
+    for data in [png(256, 256, 8, 6), dib(256, 256, 32)] {
+      let bytes = ico(&[(256, 256, &data)]);
+      assert_eq!(bytes[HEADER_LEN], 0);
+      let entry = IcoFile::parse(&bytes).unwrap().entries()[0];
+      assert_eq!((entry.width, entry.height), (256, 256));
+    }
+
+    let (small, large) = (dib(32, 32, 32), png(256, 256, 8, 6));
+    let bytes = ico(&[(32, 32, &small), (256, 256, &large)]);
+    let file = IcoFile::parse(&bytes).unwrap();
+    assert!(file.best_for_size(64).is_png);
+    assert!(!file.best_for_size(32).is_png);
+    assert!(file.best_for_dpi(32, 192).is_png);
+  }
+
+  #[test]
+  fn rejects_truncated_headers() {
+    let data = dib(16, 16, 32);
+    let bytes = ico(&[(16, 16, &data)]);
+    for len in 0..HEADER_LEN {
+      assert_eq!(IcoFile::parse(&bytes[..len]), Err(ParseIcoError::TooShort), "{len} bytes");
+    }
+    // The header promises an entry that the directory is cut short of.
+    for len in HEADER_LEN..HEADER_LEN + ENTRY_LEN {
+      assert_eq!(IcoFile::parse(&bytes[..len]), Err(ParseIcoError::TooShort), "{len} bytes");
+    }
+  }
+
+  #[test]
+  fn rejects_other_headers() {
+    let data = dib(16, 16, 32);
+    let mut bytes = ico(&[(16, 16, &data)]);
+    bytes[2] = 2; // cursor
+    assert_eq!(IcoFile::parse(&bytes), Err(ParseIcoError::InvalidHeader));
+    bytes[2] = 1;
+    bytes[0] = 1; // reserved
+    assert_eq!(IcoFile::parse(&bytes), Err(ParseIcoError::InvalidHeader));
+
+    assert_eq!(IcoFile::parse(&ico(&[])), Err(ParseIcoError::Empty));
+  }
+
+  #[test]
+  fn rejects_entries_past_the_end() {
+    let (first, second) = (dib(16, 16, 32), png(32, 32, 8, 6));
+    let bytes = ico(&[(16, 16, &first), (32, 32, &second)]);
+    let second_entry = HEADER_LEN + ENTRY_LEN;
+
+    // Cut off within the last image.
+    assert_eq!(IcoFile::parse(&bytes[..bytes.len() - 1]), Err(ParseIcoError::InvalidEntry(1)));
+
+    // Offsets and sizes reaching past the end, including ones that overflow.
+    for (field, value) in
+      [(12, bytes.len() as u32), (8, second.len() as u32 + 1), (12, u32::MAX), (8, u32::MAX)]
+    {
+      let mut bytes = bytes.clone();
+      bytes[second_entry + field..second_entry + field + 4].copy_from_slice(&value.to_le_bytes());
+      assert_eq!(IcoFile::parse(&bytes), Err(ParseIcoError::InvalidEntry(1)), "field {field} = {value}");
+    }
+
+    // Images too short to hold their own header.
+    for (image, len) in [(&first, 15), (&second, 25)] {
+      let bytes = ico(&[(16, 16, &image[..len])]);
+      assert_eq!(IcoFile::parse(&bytes), Err(ParseIcoError::InvalidEntry(0)));
+    }
+  }
+}
@@ -1,2 +1,10 @@
+pub mod key;
+#[cfg(target_os = "windows")]
 pub mod keyboard;
+pub mod keymap;
+#[cfg(target_os = "windows")]
 pub mod mouse;
+pub mod scancode;
+pub mod scroll;
+pub mod send;
+pub mod shortcut;
@@ -0,0 +1,191 @@
+use {
+  super::{
+    code_to_scancode,
+    scancode_to_code,
+  },
+  crate::{
+    Handle,
+    declare_handle,
+    get_last_error,
+    input::shortcut::MODIFIER_NAMES,
+    reset_last_error,
+    user::Msg,
+  },
+  keyboard_types::{
+    Code,
+    Modifiers,
+  },
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::UI::{
+    Input::KeyboardAndMouse::{
+      self,
+      GetKeyNameTextW,
+      GetKeyboardLayout,
+      HOT_KEY_MODIFIERS,
+      MAPVK_VK_TO_VSC_EX,
+      MAPVK_VSC_TO_VK_EX,
+      MapVirtualKeyExW,
+      VIRTUAL_KEY,
+    },
+    WindowsAndMessaging::{
+      self,
+      ACCEL,
+      CreateAcceleratorTableW,
+      DestroyAcceleratorTable,
+      TranslateAcceleratorW,
+    },
+  },
+};
+
+pub use crate::input::shortcut::{
+  ParseShortcutError,
+  Shortcut,
+  key_name,
+  parse_key_name,
+};
+
+/// Returns the name the current keyboard layout gives to `code`, as shown by the system.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getkeynametextw"]
+pub fn localized_key_name(code: Code) -> Option<String> {
+  let scancode = code_to_scancode(code)?;
+  let extended = (scancode & 0xe000) == 0xe000;
+  let l_param = ((scancode & 0xff) << 16) | ((extended as u32) << 24);
+
+  let mut buffer = [0u16; 64];
+  let len = unsafe { GetKeyNameTextW(l_param as i32, buffer.as_mut_ptr(), buffer.len() as i32) };
+  match len {
+    0 => None,
+    len => Some(String::from_utf16_lossy(&buffer[..len as usize])),
+  }
+}
+
+impl Shortcut {
+  /// Like the `Display` implementation, but key and modifier names come from the current keyboard
+  /// layout. Falls back to the canonical names wherever the system doesn't provide one.
+  pub fn to_localized_string(&self) -> String {
+    let mut string = String::new();
+    for (modifier, name) in MODIFIER_NAMES {
+      if self.modifiers.contains(modifier) {
+        let localized = match modifier {
+          Modifiers::CONTROL => localized_key_name(Code::ControlLeft),
+          Modifiers::ALT => localized_key_name(Code::AltLeft),
+          Modifiers::SHIFT => localized_key_name(Code::ShiftLeft),
+          _ => None,
+        };
+        string.push_str(&localized.unwrap_or_else(|| name.to_string()));
+        string.push(Self::SEPARATOR);
+      }
+    }
+    string.push_str(&localized_key_name(self.code).unwrap_or_else(|| key_name(self.code)));
+    string
+  }
+
+  /// The virtual key that the current keyboard layout assigns to the shortcut's physical key.
+  pub fn virtual_key(&self) -> Option<VIRTUAL_KEY> {
+    let scancode = code_to_scancode(self.code)?;
+    let hkl = unsafe { GetKeyboardLayout(0) };
+    match unsafe { MapVirtualKeyExW(scancode, MAPVK_VSC_TO_VK_EX, hkl) } {
+      0 => None,
+      vkey => Some(vkey as VIRTUAL_KEY),
+    }
+  }
+
+  /// Inverse of [`Shortcut::virtual_key`].
+  pub fn from_virtual_key(modifiers: Modifiers, vkey: VIRTUAL_KEY) -> Option<Self> {
+    let hkl = unsafe { GetKeyboardLayout(0) };
+    match scancode_to_code(unsafe { MapVirtualKeyExW(vkey as u32, MAPVK_VK_TO_VSC_EX, hkl) }) {
+      Code::Unidentified => None,
+      code => Some(Self::new(modifiers, code)),
+    }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-accel"]
+  pub fn to_accel(&self, command: u16) -> Option<ACCEL> {
+    let mut flags = WindowsAndMessaging::FVIRTKEY;
+    if self.modifiers.contains(Modifiers::CONTROL) {
+      flags |= WindowsAndMessaging::FCONTROL;
+    }
+    if self.modifiers.contains(Modifiers::ALT) {
+      flags |= WindowsAndMessaging::FALT;
+    }
+    if self.modifiers.contains(Modifiers::SHIFT) {
+      flags |= WindowsAndMessaging::FSHIFT;
+    }
+    // Accelerator tables can't express the Windows key.
+    if self.modifiers.contains(Modifiers::META) {
+      return None;
+    }
+    Some(ACCEL { fVirt: flags, key: self.virtual_key()?, cmd: command })
+  }
+
+  /// The `fsModifiers` value for `RegisterHotKey`.
+  pub fn hotkey_modifiers(&self, repeat: bool) -> HOT_KEY_MODIFIERS {
+    let mut flags = 0;
+    if self.modifiers.contains(Modifiers::CONTROL) {
+      flags |= KeyboardAndMouse::MOD_CONTROL;
+    }
+    if self.modifiers.contains(Modifiers::ALT) {
+      flags |= KeyboardAndMouse::MOD_ALT;
+    }
+    if self.modifiers.contains(Modifiers::SHIFT) {
+      flags |= KeyboardAndMouse::MOD_SHIFT;
+    }
+    if self.modifiers.contains(Modifiers::META) {
+      flags |= KeyboardAndMouse::MOD_WIN;
+    }
+    if !repeat {
+      flags |= KeyboardAndMouse::MOD_NOREPEAT;
+    }
+    flags
+  }
+
+  /// Inverse of [`Shortcut::hotkey_modifiers`], ignoring `MOD_NOREPEAT`.
+  pub fn modifiers_from_hotkey(flags: HOT_KEY_MODIFIERS) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::CONTROL, flags & KeyboardAndMouse::MOD_CONTROL != 0);
+    modifiers.set(Modifiers::ALT, flags & KeyboardAndMouse::MOD_ALT != 0);
+    modifiers.set(Modifiers::SHIFT, flags & KeyboardAndMouse::MOD_SHIFT != 0);
+    modifiers.set(Modifiers::META, flags & KeyboardAndMouse::MOD_WIN != 0);
+    modifiers
+  }
+}
+
+declare_handle!(
+  AcceleratorTable,
+  alias = "HACCEL",
+  doc = "https://learn.microsoft.com/en-us/windows/win32/winprog/windows-data-types#haccel"
+);
+
+impl AcceleratorTable {
+  /// Shortcuts that can't be expressed as an accelerator are skipped.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createacceleratortablew"]
+  pub fn new(entries: &[(Shortcut, u16)]) -> Result<Self> {
+    let accels: Vec<ACCEL> =
+      entries.iter().filter_map(|(shortcut, command)| shortcut.to_accel(*command)).collect();
+
+    reset_last_error();
+    let haccel = unsafe { CreateAcceleratorTableW(accels.as_ptr(), accels.len() as i32) };
+    match haccel.is_null() {
+      true => Err(get_last_error().unwrap_or(Error::empty())),
+      false => Ok(unsafe { Self::from_ptr(haccel) }),
+    }
+  }
+
+  pub fn destroy(self) {
+    unsafe { DestroyAcceleratorTable(self.to_ptr()) };
+  }
+
+  /// Returns `true` if the message was translated into a `Command` message, in which case it shouldn't
+  /// be translated or dispatched again.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-translateacceleratorw"]
+  pub fn translate(&self, msg: &Msg) -> bool {
+    let raw = msg.to_raw();
+    unsafe { TranslateAcceleratorW(raw.hwnd, self.to_ptr(), &raw) != 0 }
+  }
+}
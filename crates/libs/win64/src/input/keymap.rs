@@ -0,0 +1,1692 @@
+//!
+//! Layout tables and `KeyEvent` translation.
+//!
+//! Layouts are normally prepared from the live system in `input::keyboard::layout`, but they can also
+//! be written to and read from the plain text snapshots in [`fixtures`], which makes the translation
+//! reproducible without the layout installed.
+//!
+
+// Taken from winit as allowed by Apache 2.0 license
+
+pub mod fixtures;
+
+use {
+  super::{
+    key::Key,
+    scancode::{
+      ExScancode,
+      scancode_to_code,
+      vkey_location,
+    },
+  },
+  keyboard_types::{
+    Code,
+    KeyState,
+    Location,
+    Modifiers,
+    NamedKey,
+  },
+  std::{
+    collections::{
+      HashMap,
+      HashSet,
+    },
+    fmt::{
+      self,
+      Display,
+      Write,
+    },
+    sync::LazyLock,
+  },
+  windows_sys::Win32::{
+    System::SystemServices::{
+      LANG_JAPANESE,
+      LANG_KOREAN,
+      MK_CONTROL,
+      MK_SHIFT,
+    },
+    UI::Input::KeyboardAndMouse::{
+      VIRTUAL_KEY,
+      VK_ACCEPT,
+      VK_ADD,
+      VK_APPS,
+      VK_ATTN,
+      VK_BACK,
+      VK_BROWSER_BACK,
+      VK_BROWSER_FAVORITES,
+      VK_BROWSER_FORWARD,
+      VK_BROWSER_HOME,
+      VK_BROWSER_REFRESH,
+      VK_BROWSER_SEARCH,
+      VK_BROWSER_STOP,
+      VK_CANCEL,
+      VK_CAPITAL,
+      VK_CLEAR,
+      VK_CONTROL,
+      VK_CONVERT,
+      VK_CRSEL,
+      VK_DECIMAL,
+      VK_DELETE,
+      VK_DIVIDE,
+      VK_DOWN,
+      VK_END,
+      VK_EREOF,
+      VK_ESCAPE,
+      VK_EXECUTE,
+      VK_EXSEL,
+      VK_F1,
+      VK_F2,
+      VK_F3,
+      VK_F4,
+      VK_F5,
+      VK_F6,
+      VK_F7,
+      VK_F8,
+      VK_F9,
+      VK_F10,
+      VK_F11,
+      VK_F12,
+      VK_F13,
+      VK_F14,
+      VK_F15,
+      VK_F16,
+      VK_F17,
+      VK_F18,
+      VK_F19,
+      VK_F20,
+      VK_F21,
+      VK_F22,
+      VK_F23,
+      VK_F24,
+      VK_FINAL,
+      VK_GAMEPAD_A,
+      VK_GAMEPAD_B,
+      VK_GAMEPAD_DPAD_DOWN,
+      VK_GAMEPAD_DPAD_LEFT,
+      VK_GAMEPAD_DPAD_RIGHT,
+      VK_GAMEPAD_DPAD_UP,
+      VK_GAMEPAD_LEFT_SHOULDER,
+      VK_GAMEPAD_LEFT_THUMBSTICK_BUTTON,
+      VK_GAMEPAD_LEFT_THUMBSTICK_DOWN,
+      VK_GAMEPAD_LEFT_THUMBSTICK_LEFT,
+      VK_GAMEPAD_LEFT_THUMBSTICK_RIGHT,
+      VK_GAMEPAD_LEFT_THUMBSTICK_UP,
+      VK_GAMEPAD_LEFT_TRIGGER,
+      VK_GAMEPAD_MENU,
+      VK_GAMEPAD_RIGHT_SHOULDER,
+      VK_GAMEPAD_RIGHT_THUMBSTICK_BUTTON,
+      VK_GAMEPAD_RIGHT_THUMBSTICK_DOWN,
+      VK_GAMEPAD_RIGHT_THUMBSTICK_LEFT,
+      VK_GAMEPAD_RIGHT_THUMBSTICK_RIGHT,
+      VK_GAMEPAD_RIGHT_THUMBSTICK_UP,
+      VK_GAMEPAD_RIGHT_TRIGGER,
+      VK_GAMEPAD_VIEW,
+      VK_GAMEPAD_X,
+      VK_GAMEPAD_Y,
+      VK_HANGUL,
+      VK_HANJA,
+      VK_HELP,
+      VK_HOME,
+      VK_ICO_00,
+      VK_ICO_CLEAR,
+      VK_ICO_HELP,
+      VK_INSERT,
+      VK_JUNJA,
+      VK_KANA,
+      VK_KANJI,
+      VK_LAUNCH_APP1,
+      VK_LAUNCH_APP2,
+      VK_LAUNCH_MAIL,
+      VK_LAUNCH_MEDIA_SELECT,
+      VK_LBUTTON,
+      VK_LCONTROL,
+      VK_LEFT,
+      VK_LMENU,
+      VK_LSHIFT,
+      VK_LWIN,
+      VK_MBUTTON,
+      VK_MEDIA_NEXT_TRACK,
+      VK_MEDIA_PLAY_PAUSE,
+      VK_MEDIA_PREV_TRACK,
+      VK_MEDIA_STOP,
+      VK_MENU,
+      VK_MODECHANGE,
+      VK_MULTIPLY,
+      VK_NAVIGATION_ACCEPT,
+      VK_NAVIGATION_CANCEL,
+      VK_NAVIGATION_DOWN,
+      VK_NAVIGATION_LEFT,
+      VK_NAVIGATION_MENU,
+      VK_NAVIGATION_RIGHT,
+      VK_NAVIGATION_UP,
+      VK_NAVIGATION_VIEW,
+      VK_NEXT,
+      VK_NONAME,
+      VK_NONCONVERT,
+      VK_NUMLOCK,
+      VK_NUMPAD0,
+      VK_NUMPAD1,
+      VK_NUMPAD2,
+      VK_NUMPAD3,
+      VK_NUMPAD4,
+      VK_NUMPAD5,
+      VK_NUMPAD6,
+      VK_NUMPAD7,
+      VK_NUMPAD8,
+      VK_NUMPAD9,
+      VK_OEM_1,
+      VK_OEM_2,
+      VK_OEM_3,
+      VK_OEM_4,
+      VK_OEM_5,
+      VK_OEM_6,
+      VK_OEM_7,
+      VK_OEM_8,
+      VK_OEM_102,
+      VK_OEM_ATTN,
+      VK_OEM_AUTO,
+      VK_OEM_AX,
+      VK_OEM_BACKTAB,
+      VK_OEM_CLEAR,
+      VK_OEM_COMMA,
+      VK_OEM_COPY,
+      VK_OEM_CUSEL,
+      VK_OEM_ENLW,
+      VK_OEM_FINISH,
+      VK_OEM_FJ_LOYA,
+      VK_OEM_FJ_MASSHOU,
+      VK_OEM_FJ_ROYA,
+      VK_OEM_FJ_TOUROKU,
+      VK_OEM_JUMP,
+      VK_OEM_MINUS,
+      VK_OEM_NEC_EQUAL,
+      VK_OEM_PA1,
+      VK_OEM_PA2,
+      VK_OEM_PA3,
+      VK_OEM_PERIOD,
+      VK_OEM_PLUS,
+      VK_OEM_RESET,
+      VK_OEM_WSCTRL,
+      VK_PA1,
+      VK_PACKET,
+      VK_PAUSE,
+      VK_PLAY,
+      VK_PRINT,
+      VK_PRIOR,
+      VK_PROCESSKEY,
+      VK_RBUTTON,
+      VK_RCONTROL,
+      VK_RETURN,
+      VK_RIGHT,
+      VK_RMENU,
+      VK_RSHIFT,
+      VK_RWIN,
+      VK_SCROLL,
+      VK_SELECT,
+      VK_SEPARATOR,
+      VK_SHIFT,
+      VK_SLEEP,
+      VK_SNAPSHOT,
+      VK_SPACE,
+      VK_SUBTRACT,
+      VK_TAB,
+      VK_UP,
+      VK_VOLUME_DOWN,
+      VK_VOLUME_MUTE,
+      VK_VOLUME_UP,
+      VK_XBUTTON1,
+      VK_XBUTTON2,
+      VK_ZOOM,
+    },
+  },
+};
+
+fn primary_lang_id(hkl: u64) -> u16 {
+  (hkl as u16) & 0x3ff
+}
+
+/// Virtual keys that only the numpad produces, and only while NumLock is on.
+pub const NUMPAD_VKEYS: [VIRTUAL_KEY; 16] = [
+  VK_NUMPAD0,
+  VK_NUMPAD1,
+  VK_NUMPAD2,
+  VK_NUMPAD3,
+  VK_NUMPAD4,
+  VK_NUMPAD5,
+  VK_NUMPAD6,
+  VK_NUMPAD7,
+  VK_NUMPAD8,
+  VK_NUMPAD9,
+  VK_MULTIPLY,
+  VK_ADD,
+  VK_SEPARATOR,
+  VK_SUBTRACT,
+  VK_DECIMAL,
+  VK_DIVIDE,
+];
+
+/// Physical numpad keys whose meaning depends on NumLock.
+pub static NUMPAD_KEYCODES: LazyLock<HashSet<Code>> = LazyLock::new(|| {
+  let mut keycodes = HashSet::new();
+  keycodes.insert(Code::Numpad0);
+  keycodes.insert(Code::Numpad1);
+  keycodes.insert(Code::Numpad2);
+  keycodes.insert(Code::Numpad3);
+  keycodes.insert(Code::Numpad4);
+  keycodes.insert(Code::Numpad5);
+  keycodes.insert(Code::Numpad6);
+  keycodes.insert(Code::Numpad7);
+  keycodes.insert(Code::Numpad8);
+  keycodes.insert(Code::Numpad9);
+  keycodes.insert(Code::NumpadMultiply);
+  keycodes.insert(Code::NumpadAdd);
+  keycodes.insert(Code::NumpadComma);
+  keycodes.insert(Code::NumpadSubtract);
+  keycodes.insert(Code::NumpadDecimal);
+  keycodes.insert(Code::NumpadDivide);
+  keycodes
+});
+
+bitflags::bitflags! {
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+  pub struct WindowsModifiers : u8 {
+    const SHIFT = 1 << 0;
+    const CONTROL = 1 << 1;
+    const ALT = 1 << 2;
+    const CAPS_LOCK = 1 << 3;
+    const FLAGS_END = 1 << 4;
+  }
+}
+
+impl WindowsModifiers {
+  pub fn active_modifiers(key_state: &[u8; 256]) -> WindowsModifiers {
+    let shift = key_state[VK_SHIFT as usize] & 0x80 != 0;
+    let lshift = key_state[VK_LSHIFT as usize] & 0x80 != 0;
+    let rshift = key_state[VK_RSHIFT as usize] & 0x80 != 0;
+
+    let control = key_state[VK_CONTROL as usize] & 0x80 != 0;
+    let lcontrol = key_state[VK_LCONTROL as usize] & 0x80 != 0;
+    let rcontrol = key_state[VK_RCONTROL as usize] & 0x80 != 0;
+
+    let alt = key_state[VK_MENU as usize] & 0x80 != 0;
+    let lalt = key_state[VK_LMENU as usize] & 0x80 != 0;
+    let ralt = key_state[VK_RMENU as usize] & 0x80 != 0;
+
+    let caps = key_state[VK_CAPITAL as usize] & 0x01 != 0;
+
+    let mut result = WindowsModifiers::empty();
+    if shift || lshift || rshift {
+      result.insert(WindowsModifiers::SHIFT);
+    }
+    if control || lcontrol || rcontrol {
+      result.insert(WindowsModifiers::CONTROL);
+    }
+    if alt || lalt || ralt {
+      result.insert(WindowsModifiers::ALT);
+    }
+    if caps {
+      result.insert(WindowsModifiers::CAPS_LOCK);
+    }
+
+    result
+  }
+
+  pub fn apply_to_kbd_state(self, key_state: &mut [u8; 256]) {
+    if self.intersects(Self::SHIFT) {
+      key_state[VK_SHIFT as usize] |= 0x80;
+    } else {
+      key_state[VK_SHIFT as usize] &= !0x80;
+      key_state[VK_LSHIFT as usize] &= !0x80;
+      key_state[VK_RSHIFT as usize] &= !0x80;
+    }
+    if self.intersects(Self::CONTROL) {
+      key_state[VK_CONTROL as usize] |= 0x80;
+    } else {
+      key_state[VK_CONTROL as usize] &= !0x80;
+      key_state[VK_LCONTROL as usize] &= !0x80;
+      key_state[VK_RCONTROL as usize] &= !0x80;
+    }
+    if self.intersects(Self::ALT) {
+      key_state[VK_MENU as usize] |= 0x80;
+    } else {
+      key_state[VK_MENU as usize] &= !0x80;
+      key_state[VK_LMENU as usize] &= !0x80;
+      key_state[VK_RMENU as usize] &= !0x80;
+    }
+    if self.intersects(Self::CAPS_LOCK) {
+      key_state[VK_CAPITAL as usize] |= 0x01;
+    } else {
+      key_state[VK_CAPITAL as usize] &= !0x01;
+    }
+  }
+
+  /// Removes the control modifier if the alt modifier is not present.
+  /// This is useful because on Windows: (Control + Alt) == AltGr
+  /// but we don't want to interfere with the AltGr state.
+  pub fn remove_only_ctrl(mut self) -> WindowsModifiers {
+    if !self.contains(WindowsModifiers::ALT) {
+      self.remove(WindowsModifiers::CONTROL);
+    }
+    self
+  }
+}
+
+bitflags::bitflags! {
+  /// Modifier keys along with the side they are held on, and the lock keys that are toggled on.
+  ///
+  /// On layouts with AltGr, right alt is reported as `AltGraph` alone. Windows also holds left
+  /// control for as long as AltGr is down, so `LeftControl` is left out while `AltGraph` is set.
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  pub struct ModifierState: u16 {
+    const LeftShift = 1 << 0;
+    const RightShift = 1 << 1;
+    const LeftControl = 1 << 2;
+    const RightControl = 1 << 3;
+    const LeftAlt = 1 << 4;
+    const RightAlt = 1 << 5;
+    const LeftMeta = 1 << 6;
+    const RightMeta = 1 << 7;
+    const AltGraph = 1 << 8;
+    const CapsLock = 1 << 9;
+    const NumLock = 1 << 10;
+    const ScrollLock = 1 << 11;
+  }
+}
+
+impl ModifierState {
+  pub const ALT: Self = Self::LeftAlt.union(Self::RightAlt);
+  pub const CONTROL: Self = Self::LeftControl.union(Self::RightControl);
+  pub const META: Self = Self::LeftMeta.union(Self::RightMeta);
+  pub const SHIFT: Self = Self::LeftShift.union(Self::RightShift);
+
+  /// Reads the state from a keyboard state array as returned by `GetKeyboardState`.
+  pub fn from_kbd_state(kbd_state: &[u8; 256], has_alt_graph: bool) -> Self {
+    let pressed = |vkey: VIRTUAL_KEY| kbd_state[vkey as usize] & 0x80 != 0;
+    let toggled = |vkey: VIRTUAL_KEY| kbd_state[vkey as usize] & 0x01 != 0;
+    let alt_graph = has_alt_graph && pressed(VK_RMENU);
+
+    let mut state = Self::empty();
+    state.set(Self::LeftShift, pressed(VK_LSHIFT));
+    state.set(Self::RightShift, pressed(VK_RSHIFT));
+    state.set(Self::LeftControl, pressed(VK_LCONTROL) && !alt_graph);
+    state.set(Self::RightControl, pressed(VK_RCONTROL));
+    state.set(Self::LeftAlt, pressed(VK_LMENU));
+    state.set(Self::RightAlt, pressed(VK_RMENU) && !alt_graph);
+    state.set(Self::LeftMeta, pressed(VK_LWIN));
+    state.set(Self::RightMeta, pressed(VK_RWIN));
+    state.set(Self::AltGraph, alt_graph);
+    state.set(Self::CapsLock, toggled(VK_CAPITAL));
+    state.set(Self::NumLock, toggled(VK_NUMLOCK));
+    state.set(Self::ScrollLock, toggled(VK_SCROLL));
+    state
+  }
+
+  /// Like [`ModifierState::from_kbd_state`], for mouse messages. Their `MK_SHIFT` and `MK_CONTROL` flags
+  /// decide whether shift and control are held, while `kbd_state` tells the sides apart and provides the
+  /// other modifiers.
+  pub fn from_mouse_flags(flags: u32, kbd_state: &[u8; 256], has_alt_graph: bool) -> Self {
+    let mut state = Self::from_kbd_state(kbd_state, has_alt_graph);
+    for (flag, both, left) in
+      [(MK_SHIFT, Self::SHIFT, Self::LeftShift), (MK_CONTROL, Self::CONTROL, Self::LeftControl)]
+    {
+      if flags & flag == 0 {
+        state.remove(both);
+      } else if !state.intersects(both.union(Self::AltGraph)) {
+        // Held according to the message but not the keyboard state, e.g. for input from another thread.
+        state.insert(left);
+      }
+    }
+    state
+  }
+
+  /// The flag for the key behind `code`, if it is a modifier or lock key.
+  pub fn from_code(code: Code, has_alt_graph: bool) -> Option<Self> {
+    Some(match code {
+      Code::ShiftLeft => Self::LeftShift,
+      Code::ShiftRight => Self::RightShift,
+      Code::ControlLeft => Self::LeftControl,
+      Code::ControlRight => Self::RightControl,
+      Code::AltLeft => Self::LeftAlt,
+      Code::AltRight if has_alt_graph => Self::AltGraph,
+      Code::AltRight => Self::RightAlt,
+      Code::MetaLeft => Self::LeftMeta,
+      Code::MetaRight => Self::RightMeta,
+      Code::CapsLock => Self::CapsLock,
+      Code::NumLock => Self::NumLock,
+      Code::ScrollLock => Self::ScrollLock,
+      _ => return None,
+    })
+  }
+
+  /// Folds both sides of each modifier together.
+  pub fn to_modifiers(self) -> Modifiers {
+    let mut mods = Modifiers::empty();
+    mods.set(Modifiers::SHIFT, self.intersects(Self::SHIFT));
+    mods.set(Modifiers::CONTROL, self.intersects(Self::CONTROL));
+    mods.set(Modifiers::ALT, self.intersects(Self::ALT));
+    mods.set(Modifiers::META, self.intersects(Self::META));
+    mods.set(Modifiers::ALT_GRAPH, self.contains(Self::AltGraph));
+    mods.set(Modifiers::CAPS_LOCK, self.contains(Self::CapsLock));
+    mods.set(Modifiers::NUM_LOCK, self.contains(Self::NumLock));
+    mods.set(Modifiers::SCROLL_LOCK, self.contains(Self::ScrollLock));
+    mods
+  }
+}
+
+impl Default for ModifierState {
+  fn default() -> Self {
+    Self::empty()
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModifiersChangedEvent {
+  pub previous: ModifierState,
+  pub current: ModifierState,
+}
+
+impl ModifiersChangedEvent {
+  /// The change caused by a key message, or `None` if the key is not a modifier or the state
+  /// stayed the same (e.g. on repeats).
+  ///
+  /// The keyboard state already includes the key by the time the message is received, so the
+  /// previous state is found by undoing the key.
+  pub fn from_key_input(input: &RawKeyInput, has_alt_graph: bool) -> Option<Self> {
+    if input.is_repeat {
+      return None;
+    }
+
+    let vkey = match scancode_to_code(input.scancode as u32) {
+      Code::ShiftLeft => VK_LSHIFT,
+      Code::ShiftRight => VK_RSHIFT,
+      Code::ControlLeft => VK_LCONTROL,
+      Code::ControlRight => VK_RCONTROL,
+      Code::AltLeft => VK_LMENU,
+      Code::AltRight => VK_RMENU,
+      Code::MetaLeft => VK_LWIN,
+      Code::MetaRight => VK_RWIN,
+      Code::CapsLock => VK_CAPITAL,
+      Code::NumLock => VK_NUMLOCK,
+      Code::ScrollLock => VK_SCROLL,
+      _ => return None,
+    };
+
+    let mut previous_state = input.kbd_state;
+    if matches!(vkey, VK_CAPITAL | VK_NUMLOCK | VK_SCROLL) {
+      // Lock keys toggle on press and leave the toggle alone on release
+      if input.is_pressed {
+        previous_state[vkey as usize] ^= 0x01;
+      }
+    } else if input.is_pressed {
+      previous_state[vkey as usize] &= !0x80;
+    } else {
+      previous_state[vkey as usize] |= 0x80;
+    }
+
+    let previous = ModifierState::from_kbd_state(&previous_state, has_alt_graph);
+    let current = ModifierState::from_kbd_state(&input.kbd_state, has_alt_graph);
+    (previous != current).then_some(Self { previous, current })
+  }
+}
+
+pub struct Layout {
+  pub hkl: u64,
+
+  /// Maps numpad keys from Windows virtual key to a `Key`.
+  ///
+  /// This is useful because some numpad keys generate different characters based on the locale.
+  /// For example `VK_DECIMAL` is sometimes "." and sometimes ",". Note: numpad-specific virtual
+  /// keys are only produced by Windows when the NumLock is active.
+  ///
+  /// Making this field separate from the `keys` field saves having to add NumLock as a modifier
+  /// to `WindowsModifiers`, which would double the number of items in keys.
+  pub numlock_on_keys: HashMap<VIRTUAL_KEY, Key>,
+  /// Like `numlock_on_keys` but this will map to the key that would be produced if numlock was
+  /// off. The keys of this map are identical to the keys of `numlock_on_keys`.
+  pub numlock_off_keys: HashMap<VIRTUAL_KEY, Key>,
+
+  /// Maps a modifier state to group of key strings
+  /// We're not using `ModifiersState` here because that object cannot express caps lock,
+  /// but we need to handle caps lock too.
+  ///
+  /// This map shouldn't need to exist.
+  /// However currently this seems to be the only good way
+  /// of getting the label for the pressed key. Note that calling `ToUnicode`
+  /// just when the key is pressed/released would be enough if `ToUnicode` wouldn't
+  /// change the keyboard state (it clears the dead key). There is a flag to prevent
+  /// changing the state, but that flag requires Windows 10, version 1607 or newer)
+  pub keys: HashMap<WindowsModifiers, HashMap<Code, Key>>,
+  pub has_alt_graph: bool,
+}
+
+impl Layout {
+  /// The layout independent modifiers held in `kbd_state`. On layouts with AltGr, right alt is
+  /// reported as neither control nor alt.
+  pub fn agnostic_modifiers(&self, kbd_state: &[u8; 256]) -> Modifiers {
+    let pressed = |vkey: VIRTUAL_KEY| kbd_state[vkey as usize] & 0x80 != 0;
+    let filter_out_altgr = self.has_alt_graph && pressed(VK_RMENU);
+    let mut mods = Modifiers::empty();
+    mods.set(Modifiers::SHIFT, pressed(VK_SHIFT));
+    mods.set(Modifiers::CONTROL, pressed(VK_CONTROL) && !filter_out_altgr);
+    mods.set(Modifiers::ALT, pressed(VK_MENU) && !filter_out_altgr);
+    mods.set(Modifiers::META, pressed(VK_LWIN) || pressed(VK_RWIN));
+    mods
+  }
+
+  pub fn get_key(&self, mods: WindowsModifiers, num_lock_on: bool, vkey: VIRTUAL_KEY, code: &Code) -> Key {
+    // let native_code = NativeKey::Windows(vkey);
+
+    let unknown_alt = vkey == VK_MENU;
+    if !unknown_alt {
+      // Here we try using the virtual key directly but if the virtual key doesn't distinguish
+      // between left and right alt, we can't report AltGr. Therefore, we only do this if the
+      // key is not the "unknown alt" key.
+      //
+      // The reason for using the virtual key directly is that `MapVirtualKeyExW` (used when
+      // building the keys map) sometimes maps virtual keys to odd scancodes that don't match
+      // the scancode coming from the KEYDOWN message for the same key. For example: `VK_LEFT`
+      // is mapped to `0x004B`, but the scancode for the left arrow is `0xE04B`.
+      let key_from_vkey = vkey_to_non_char_key(vkey, self.hkl, self.has_alt_graph);
+
+      if !matches!(key_from_vkey, Key::Unidentified(_)) {
+        return key_from_vkey;
+      }
+    }
+    if num_lock_on {
+      if let Some(key) = self.numlock_on_keys.get(&vkey) {
+        return key.clone();
+      }
+    } else if let Some(key) = self.numlock_off_keys.get(&vkey) {
+      return key.clone();
+    }
+
+    if let Some(keys) = self.keys.get(&mods)
+      && let Some(key) = keys.get(code)
+    {
+      return key.clone();
+    }
+    Key::Unidentified(vkey)
+  }
+
+  /// Like `get_key`, but for callers that only know the physical key. Keys that aren't part of
+  /// the layout's character tables are resolved through their default virtual key.
+  pub fn get_key_for_code(&self, mods: WindowsModifiers, code: Code) -> Key {
+    if let Some(keys) = self.keys.get(&mods)
+      && let Some(key) = keys.get(&code)
+    {
+      return key.clone();
+    }
+    vkey_to_non_char_key(keycode_to_vkey(code, self.hkl), self.hkl, self.has_alt_graph)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseLayoutError {
+  /// The line is neither a `[section]` header nor a `name = value` entry.
+  Syntax(usize),
+  UnknownSection(usize),
+  UnknownSetting(usize),
+  UnknownCode(usize),
+  InvalidVirtualKey(usize),
+  InvalidValue(usize),
+}
+
+impl Display for ParseLayoutError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Syntax(line) => write!(f, "line {line}: expected a section header or an entry"),
+      Self::UnknownSection(line) => write!(f, "line {line}: unrecognized section"),
+      Self::UnknownSetting(line) => write!(f, "line {line}: unrecognized setting"),
+      Self::UnknownCode(line) => write!(f, "line {line}: unrecognized key code"),
+      Self::InvalidVirtualKey(line) => write!(f, "line {line}: invalid virtual key"),
+      Self::InvalidValue(line) => write!(f, "line {line}: invalid value"),
+    }
+  }
+}
+
+impl std::error::Error for ParseLayoutError {}
+
+enum Section {
+  Settings,
+  NumlockOn,
+  NumlockOff,
+  Keys(WindowsModifiers),
+}
+
+impl Layout {
+  /// A layout with empty tables. Keys missing from the tables resolve through their virtual key
+  /// alone, the same way keys without a character do.
+  pub fn new(hkl: u64) -> Self {
+    Self {
+      hkl,
+      numlock_on_keys: HashMap::new(),
+      numlock_off_keys: HashMap::new(),
+      keys: HashMap::new(),
+      has_alt_graph: false,
+    }
+  }
+
+  /// Writes the layout in the plain text format read by [`Layout::from_snapshot`].
+  ///
+  /// ```text
+  /// hkl = 0x0000000004070407
+  /// alt_graph = true
+  ///
+  /// [numlock on]
+  /// 0x006e = ","
+  ///
+  /// [keys SHIFT]
+  /// KeyA = "A"
+  /// Equal = dead '`'
+  /// ```
+  ///
+  /// Keys are written as a quoted string for characters, `dead` followed by an optional quoted
+  /// char for dead keys, `vk` followed by the virtual key for unidentified keys, and by name for
+  /// named keys. Entries are sorted, so snapshots of the same layout compare equal.
+  pub fn to_snapshot(&self) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "hkl = {:#018x}", self.hkl);
+    let _ = writeln!(out, "alt_graph = {}", self.has_alt_graph);
+
+    for (name, keys) in [("numlock on", &self.numlock_on_keys), ("numlock off", &self.numlock_off_keys)] {
+      let _ = writeln!(out, "\n[{name}]");
+      let mut keys: Vec<_> = keys.iter().collect();
+      keys.sort();
+      for (vkey, key) in keys {
+        let _ = writeln!(out, "{vkey:#06x} = {}", SnapshotKey(key));
+      }
+    }
+
+    let mut mod_states: Vec<_> = self.keys.keys().copied().collect();
+    mod_states.sort_by_key(|mods| mods.bits());
+    for mods in mod_states {
+      out.push_str("\n[keys");
+      if !mods.is_empty() {
+        out.push(' ');
+        let _ = bitflags::parser::to_writer(&mods, &mut out);
+      }
+      out.push_str("]\n");
+
+      let mut keys: Vec<_> = self.keys[&mods].iter().collect();
+      keys.sort();
+      for (code, key) in keys {
+        let _ = writeln!(out, "{code} = {}", SnapshotKey(key));
+      }
+    }
+
+    out
+  }
+
+  /// Reads a layout written by [`Layout::to_snapshot`]. Lines starting with `#` are comments.
+  pub fn from_snapshot(snapshot: &str) -> std::result::Result<Self, ParseLayoutError> {
+    let mut layout = Self::new(0);
+    let mut section = Section::Settings;
+
+    for (index, line) in snapshot.lines().enumerate() {
+      let number = index + 1;
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      if let Some(header) = line.strip_prefix('[') {
+        let header = header.strip_suffix(']').ok_or(ParseLayoutError::Syntax(number))?.trim();
+        section = match header {
+          "numlock on" => Section::NumlockOn,
+          "numlock off" => Section::NumlockOff,
+          "keys" => Section::Keys(WindowsModifiers::empty()),
+          _ => match header.strip_prefix("keys ") {
+            Some(mods) => Section::Keys(
+              bitflags::parser::from_str(mods).map_err(|_| ParseLayoutError::UnknownSection(number))?,
+            ),
+            None => return Err(ParseLayoutError::UnknownSection(number)),
+          },
+        };
+        if let Section::Keys(mods) = section {
+          layout.keys.entry(mods).or_default();
+        }
+        continue;
+      }
+
+      let (name, value) = line.split_once('=').ok_or(ParseLayoutError::Syntax(number))?;
+      let (name, value) = (name.trim(), value.trim());
+      match section {
+        Section::Settings => match name {
+          "hkl" => layout.hkl = parse_hex(value).ok_or(ParseLayoutError::InvalidValue(number))?,
+          "alt_graph" => {
+            layout.has_alt_graph = value.parse().map_err(|_| ParseLayoutError::InvalidValue(number))?
+          },
+          _ => return Err(ParseLayoutError::UnknownSetting(number)),
+        },
+        Section::NumlockOn | Section::NumlockOff => {
+          let vkey = parse_hex(name)
+            .and_then(|vkey| VIRTUAL_KEY::try_from(vkey).ok())
+            .ok_or(ParseLayoutError::InvalidVirtualKey(number))?;
+          let key = parse_key(value).ok_or(ParseLayoutError::InvalidValue(number))?;
+          match section {
+            Section::NumlockOn => layout.numlock_on_keys.insert(vkey, key),
+            _ => layout.numlock_off_keys.insert(vkey, key),
+          };
+        },
+        Section::Keys(mods) => {
+          let code: Code = name.parse().map_err(|_| ParseLayoutError::UnknownCode(number))?;
+          let key = parse_key(value).ok_or(ParseLayoutError::InvalidValue(number))?;
+          layout.keys.entry(mods).or_default().insert(code, key);
+        },
+      }
+    }
+
+    Ok(layout)
+  }
+}
+
+struct SnapshotKey<'a>(&'a Key);
+
+impl Display for SnapshotKey<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.0 {
+      Key::Named(named) => write!(f, "{named}"),
+      Key::Character(text) => write!(f, "{text:?}"),
+      Key::Unidentified(vkey) => write!(f, "vk {vkey:#06x}"),
+      Key::Dead(Some(ch)) => write!(f, "dead {ch:?}"),
+      Key::Dead(None) => write!(f, "dead"),
+    }
+  }
+}
+
+fn parse_hex(value: &str) -> Option<u64> {
+  u64::from_str_radix(value.strip_prefix("0x")?, 16).ok()
+}
+
+fn parse_key(value: &str) -> Option<Key> {
+  if value.starts_with('"') {
+    return parse_literal(value, '"').map(Key::Character);
+  }
+  if let Some(rest) = value.strip_prefix("dead") {
+    let rest = rest.trim();
+    if rest.is_empty() {
+      return Some(Key::Dead(None));
+    }
+    let text = parse_literal(rest, '\'')?;
+    let mut chars = text.chars();
+    return match (chars.next(), chars.next()) {
+      (Some(ch), None) => Some(Key::Dead(Some(ch))),
+      _ => None,
+    };
+  }
+  if let Some(vkey) = value.strip_prefix("vk ") {
+    return parse_hex(vkey.trim()).and_then(|vkey| VIRTUAL_KEY::try_from(vkey).ok()).map(Key::Unidentified);
+  }
+  value.parse().ok().map(Key::Named)
+}
+
+/// Reads a string or char literal as written by `Debug`.
+fn parse_literal(literal: &str, quote: char) -> Option<String> {
+  let inner = literal.strip_prefix(quote)?.strip_suffix(quote)?;
+  let mut text = String::with_capacity(inner.len());
+  let mut chars = inner.chars();
+  while let Some(ch) = chars.next() {
+    if ch != '\\' {
+      text.push(ch);
+      continue;
+    }
+    text.push(match chars.next()? {
+      'n' => '\n',
+      'r' => '\r',
+      't' => '\t',
+      '0' => '\0',
+      '\\' => '\\',
+      '"' => '"',
+      '\'' => '\'',
+      'u' => {
+        let (hex, rest) = chars.as_str().strip_prefix('{')?.split_once('}')?;
+        let ch = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+        chars = rest.chars();
+        ch
+      },
+      _ => return None,
+    });
+  }
+  Some(text)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyEvent {
+  pub state: KeyState,
+  pub key: Key,
+  pub code: Code,
+  pub location: Location,
+  pub modifiers: Modifiers,
+  /// Like `modifiers`, but keeps track of which side each modifier is held on.
+  pub modifier_state: ModifierState,
+  pub repeat: bool,
+  pub key_without_modifiers: Key,
+}
+
+/// Everything `KeyEvent` translation reads from a `WM_KEYDOWN` or `WM_KEYUP` message and the
+/// keyboard state at the time it was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawKeyInput {
+  pub vkey: VIRTUAL_KEY,
+  pub scancode: ExScancode,
+  pub is_pressed: bool,
+  pub is_repeat: bool,
+  /// As returned by `GetKeyboardState`.
+  pub kbd_state: [u8; 256],
+}
+
+impl RawKeyInput {
+  /// A keyboard state with nothing pressed and no lock keys toggled.
+  pub const EMPTY_KBD_STATE: [u8; 256] = [0; 256];
+}
+
+impl KeyEvent {
+  /// Translates a key message against `layout` without consulting any global keyboard state.
+  pub fn translate(layout: &Layout, input: &RawKeyInput) -> Self {
+    const NO_MODS: WindowsModifiers = WindowsModifiers::empty();
+
+    let RawKeyInput { vkey, scancode, is_pressed, is_repeat, ref kbd_state } = *input;
+    let code = scancode_to_code(scancode as u32);
+    let location = match code {
+      Code::ShiftLeft | Code::ControlLeft | Code::AltLeft | Code::MetaLeft => Location::Left,
+      Code::ShiftRight | Code::ControlRight | Code::AltRight | Code::MetaRight => Location::Right,
+      _ => vkey_location(vkey, (scancode & 0xe000) == 0xe000),
+    };
+
+    let modifiers = layout.agnostic_modifiers(kbd_state);
+    let modifier_state = ModifierState::from_kbd_state(kbd_state, layout.has_alt_graph);
+    let mods = WindowsModifiers::active_modifiers(kbd_state);
+    let mods_without_ctrl = mods.remove_only_ctrl();
+    let num_lock_on = kbd_state[VK_NUMLOCK as usize] & 1 != 0;
+
+    // On Windows Ctrl+NumLock = Pause (and apparently Ctrl+Pause -> NumLock). In these cases
+    // the KeyCode still stores the real key, so in the name of consistency across platforms, we
+    // circumvent this mapping and force the key values to match the keycode.
+    // For more on this, read the article by Raymond Chen, titled:
+    // "Why does Ctrl+ScrollLock cancel dialogs?"
+    // https://devblogs.microsoft.com/oldnewthing/20080211-00/?p=23503
+    let code_as_key = if mods.contains(WindowsModifiers::CONTROL) {
+      match code {
+        Code::NumLock => Some(Key::Named(NamedKey::NumLock)),
+        Code::Pause => Some(Key::Named(NamedKey::Pause)),
+        _ => None,
+      }
+    } else {
+      None
+    };
+
+    // Dead keys are reported as such, and the key after one as its own character. The composed
+    // text only comes with the `Char` message that follows.
+    let key = match code_as_key.clone() {
+      Some(key) => key,
+      None => layout.get_key(mods_without_ctrl, num_lock_on, vkey, &code),
+    };
+    let key_without_modifiers = if let Some(key) = code_as_key {
+      key
+    } else {
+      match layout.get_key(NO_MODS, false, vkey, &code) {
+        // We convert dead keys into their character.
+        // The reason for this is that `key_without_modifiers` is designed for key-bindings,
+        // but the US International layout treats `'` (apostrophe) as a dead key and the
+        // regular US layout treats it a character. In order for a single binding
+        // configuration to work with both layouts, we forward each dead key as a character.
+        Key::Dead(k) => {
+          if let Some(ch) = k {
+            // I'm avoiding the heap allocation. I don't want to talk about it :(
+            let mut utf8 = [0; 4];
+            let s = ch.encode_utf8(&mut utf8);
+            Key::Character(s.to_string())
+          } else {
+            Key::Named(NamedKey::Unidentified)
+          }
+        },
+        key => key,
+      }
+    };
+
+    Self {
+      state: if is_pressed { KeyState::Down } else { KeyState::Up },
+      key,
+      code,
+      location,
+      modifiers,
+      modifier_state,
+      repeat: is_repeat,
+      key_without_modifiers,
+    }
+  }
+}
+
+pub fn is_numpad_specific(vk: VIRTUAL_KEY) -> bool {
+  matches!(
+    vk,
+    VK_NUMPAD0
+      | VK_NUMPAD1
+      | VK_NUMPAD2
+      | VK_NUMPAD3
+      | VK_NUMPAD4
+      | VK_NUMPAD5
+      | VK_NUMPAD6
+      | VK_NUMPAD7
+      | VK_NUMPAD8
+      | VK_NUMPAD9
+      | VK_ADD
+      | VK_SUBTRACT
+      | VK_DIVIDE
+      | VK_DECIMAL
+      | VK_SEPARATOR
+  )
+}
+
+pub(crate) fn keycode_to_vkey(keycode: Code, hkl: u64) -> VIRTUAL_KEY {
+  let primary_lang_id = primary_lang_id(hkl);
+  let is_korean = primary_lang_id as u32 == LANG_KOREAN;
+  let is_japanese = primary_lang_id as u32 == LANG_JAPANESE;
+
+  match keycode {
+    Code::Backquote => 0,
+    Code::Backslash => 0,
+    Code::BracketLeft => 0,
+    Code::BracketRight => 0,
+    Code::Comma => 0,
+    Code::Digit0 => 0,
+    Code::Digit1 => 0,
+    Code::Digit2 => 0,
+    Code::Digit3 => 0,
+    Code::Digit4 => 0,
+    Code::Digit5 => 0,
+    Code::Digit6 => 0,
+    Code::Digit7 => 0,
+    Code::Digit8 => 0,
+    Code::Digit9 => 0,
+    Code::Equal => 0,
+    Code::IntlBackslash => 0,
+    Code::IntlRo => 0,
+    Code::IntlYen => 0,
+    Code::KeyA => 0,
+    Code::KeyB => 0,
+    Code::KeyC => 0,
+    Code::KeyD => 0,
+    Code::KeyE => 0,
+    Code::KeyF => 0,
+    Code::KeyG => 0,
+    Code::KeyH => 0,
+    Code::KeyI => 0,
+    Code::KeyJ => 0,
+    Code::KeyK => 0,
+    Code::KeyL => 0,
+    Code::KeyM => 0,
+    Code::KeyN => 0,
+    Code::KeyO => 0,
+    Code::KeyP => 0,
+    Code::KeyQ => 0,
+    Code::KeyR => 0,
+    Code::KeyS => 0,
+    Code::KeyT => 0,
+    Code::KeyU => 0,
+    Code::KeyV => 0,
+    Code::KeyW => 0,
+    Code::KeyX => 0,
+    Code::KeyY => 0,
+    Code::KeyZ => 0,
+    Code::Minus => 0,
+    Code::Period => 0,
+    Code::Quote => 0,
+    Code::Semicolon => 0,
+    Code::Slash => 0,
+    Code::AltLeft => VK_LMENU,
+    Code::AltRight => VK_RMENU,
+    Code::Backspace => VK_BACK,
+    Code::CapsLock => VK_CAPITAL,
+    Code::ContextMenu => VK_APPS,
+    Code::ControlLeft => VK_LCONTROL,
+    Code::ControlRight => VK_RCONTROL,
+    Code::Enter => VK_RETURN,
+    Code::MetaLeft => VK_LWIN,
+    Code::MetaRight => VK_RWIN,
+    Code::ShiftLeft => VK_RSHIFT,
+    Code::ShiftRight => VK_LSHIFT,
+    Code::Space => VK_SPACE,
+    Code::Tab => VK_TAB,
+    Code::Convert => VK_CONVERT,
+    Code::KanaMode => VK_KANA,
+    Code::Lang1 if is_korean => VK_HANGUL,
+    Code::Lang1 if is_japanese => VK_KANA,
+    Code::Lang2 if is_korean => VK_HANJA,
+    Code::Lang2 if is_japanese => 0,
+    Code::Lang3 if is_japanese => VK_OEM_FINISH,
+    Code::Lang4 if is_japanese => 0,
+    Code::Lang5 if is_japanese => 0,
+    Code::NonConvert => VK_NONCONVERT,
+    Code::Delete => VK_DELETE,
+    Code::End => VK_END,
+    Code::Help => VK_HELP,
+    Code::Home => VK_HOME,
+    Code::Insert => VK_INSERT,
+    Code::PageDown => VK_NEXT,
+    Code::PageUp => VK_PRIOR,
+    Code::ArrowDown => VK_DOWN,
+    Code::ArrowLeft => VK_LEFT,
+    Code::ArrowRight => VK_RIGHT,
+    Code::ArrowUp => VK_UP,
+    Code::NumLock => VK_NUMLOCK,
+    Code::Numpad0 => VK_NUMPAD0,
+    Code::Numpad1 => VK_NUMPAD1,
+    Code::Numpad2 => VK_NUMPAD2,
+    Code::Numpad3 => VK_NUMPAD3,
+    Code::Numpad4 => VK_NUMPAD4,
+    Code::Numpad5 => VK_NUMPAD5,
+    Code::Numpad6 => VK_NUMPAD6,
+    Code::Numpad7 => VK_NUMPAD7,
+    Code::Numpad8 => VK_NUMPAD8,
+    Code::Numpad9 => VK_NUMPAD9,
+    Code::NumpadAdd => VK_ADD,
+    Code::NumpadBackspace => VK_BACK,
+    Code::NumpadClear => VK_CLEAR,
+    Code::NumpadClearEntry => 0,
+    Code::NumpadComma => VK_SEPARATOR,
+    Code::NumpadDecimal => VK_DECIMAL,
+    Code::NumpadDivide => VK_DIVIDE,
+    Code::NumpadEnter => VK_RETURN,
+    Code::NumpadEqual => 0,
+    Code::NumpadHash => 0,
+    Code::NumpadMemoryAdd => 0,
+    Code::NumpadMemoryClear => 0,
+    Code::NumpadMemoryRecall => 0,
+    Code::NumpadMemoryStore => 0,
+    Code::NumpadMemorySubtract => 0,
+    Code::NumpadMultiply => VK_MULTIPLY,
+    Code::NumpadParenLeft => 0,
+    Code::NumpadParenRight => 0,
+    Code::NumpadStar => 0,
+    Code::NumpadSubtract => VK_SUBTRACT,
+    Code::Escape => VK_ESCAPE,
+    Code::Fn => 0,
+    Code::FnLock => 0,
+    Code::PrintScreen => VK_SNAPSHOT,
+    Code::ScrollLock => VK_SCROLL,
+    Code::Pause => VK_PAUSE,
+    Code::BrowserBack => VK_BROWSER_BACK,
+    Code::BrowserFavorites => VK_BROWSER_FAVORITES,
+    Code::BrowserForward => VK_BROWSER_FORWARD,
+    Code::BrowserHome => VK_BROWSER_HOME,
+    Code::BrowserRefresh => VK_BROWSER_REFRESH,
+    Code::BrowserSearch => VK_BROWSER_SEARCH,
+    Code::BrowserStop => VK_BROWSER_STOP,
+    Code::Eject => 0,
+    Code::LaunchApp1 => VK_LAUNCH_APP1,
+    Code::LaunchApp2 => VK_LAUNCH_APP2,
+    Code::LaunchMail => VK_LAUNCH_MAIL,
+    Code::MediaPlayPause => VK_MEDIA_PLAY_PAUSE,
+    Code::MediaSelect => VK_LAUNCH_MEDIA_SELECT,
+    Code::MediaStop => VK_MEDIA_STOP,
+    Code::MediaTrackNext => VK_MEDIA_NEXT_TRACK,
+    Code::MediaTrackPrevious => VK_MEDIA_PREV_TRACK,
+    Code::Power => 0,
+    Code::Sleep => 0,
+    Code::AudioVolumeDown => VK_VOLUME_DOWN,
+    Code::AudioVolumeMute => VK_VOLUME_MUTE,
+    Code::AudioVolumeUp => VK_VOLUME_UP,
+    Code::WakeUp => 0,
+    #[allow(deprecated)]
+    Code::Hyper => 0,
+    #[allow(deprecated)]
+    Code::Turbo => 0,
+    Code::Abort => 0,
+    Code::Resume => 0,
+    Code::Suspend => 0,
+    Code::Again => 0,
+    Code::Copy => 0,
+    Code::Cut => 0,
+    Code::Find => 0,
+    Code::Open => 0,
+    Code::Paste => 0,
+    Code::Props => 0,
+    Code::Select => VK_SELECT,
+    Code::Undo => 0,
+    Code::Hiragana => 0,
+    Code::Katakana => 0,
+    Code::F1 => VK_F1,
+    Code::F2 => VK_F2,
+    Code::F3 => VK_F3,
+    Code::F4 => VK_F4,
+    Code::F5 => VK_F5,
+    Code::F6 => VK_F6,
+    Code::F7 => VK_F7,
+    Code::F8 => VK_F8,
+    Code::F9 => VK_F9,
+    Code::F10 => VK_F10,
+    Code::F11 => VK_F11,
+    Code::F12 => VK_F12,
+    Code::F13 => VK_F13,
+    Code::F14 => VK_F14,
+    Code::F15 => VK_F15,
+    Code::F16 => VK_F16,
+    Code::F17 => VK_F17,
+    Code::F18 => VK_F18,
+    Code::F19 => VK_F19,
+    Code::F20 => VK_F20,
+    Code::F21 => VK_F21,
+    Code::F22 => VK_F22,
+    Code::F23 => VK_F23,
+    Code::F24 => VK_F24,
+    Code::F25 => 0,
+    Code::F26 => 0,
+    Code::F27 => 0,
+    Code::F28 => 0,
+    Code::F29 => 0,
+    Code::F30 => 0,
+    Code::F31 => 0,
+    Code::F32 => 0,
+    Code::F33 => 0,
+    Code::F34 => 0,
+    Code::F35 => 0,
+    _ => 0,
+  }
+}
+
+/// This converts virtual keys to `Key`s. Only virtual keys which can be unambiguously converted to
+/// a `Key`, with only the information passed in as arguments, are converted.
+///
+/// In other words: this function does not need to "prepare" the current layout in order to do
+/// the conversion, but as such it cannot convert certain keys, like language-specific character
+/// keys.
+///
+/// The result includes all non-character keys defined within `Key` plus characters from numpad
+/// keys. For example, backspace and tab are included.
+pub(crate) fn vkey_to_non_char_key(vkey: VIRTUAL_KEY, hkl: u64, has_alt_graph: bool) -> Key {
+  // List of the Web key names and their corresponding platform-native key names:
+  // https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key/Key_Values
+
+  let primary_lang_id = primary_lang_id(hkl);
+  let is_korean = primary_lang_id as u32 == LANG_KOREAN;
+  let is_japanese = primary_lang_id as u32 == LANG_JAPANESE;
+
+  match vkey {
+    VK_LBUTTON => Key::Named(NamedKey::Unidentified), // Mouse
+    VK_RBUTTON => Key::Named(NamedKey::Unidentified), // Mouse
+
+    // I don't think this can be represented with a Key
+    VK_CANCEL => Key::Named(NamedKey::Cancel),
+
+    VK_MBUTTON => Key::Named(NamedKey::Unidentified), // Mouse
+    VK_XBUTTON1 => Key::Named(NamedKey::Unidentified), // Mouse
+    VK_XBUTTON2 => Key::Named(NamedKey::Unidentified), // Mouse
+    VK_BACK => Key::Named(NamedKey::Backspace),
+    VK_TAB => Key::Named(NamedKey::Tab),
+    VK_CLEAR => Key::Named(NamedKey::Clear),
+    VK_RETURN => Key::Named(NamedKey::Enter),
+    VK_SHIFT => Key::Named(NamedKey::Shift),
+    VK_CONTROL => Key::Named(NamedKey::Control),
+    VK_MENU => Key::Named(NamedKey::Alt),
+    VK_PAUSE => Key::Named(NamedKey::Pause),
+    VK_CAPITAL => Key::Named(NamedKey::CapsLock),
+
+    // VK_HANGEUL => Key::Named(NamedKey::HangulMode), // Deprecated in favour of VK_HANGUL
+
+    // VK_HANGUL and VK_KANA are defined as the same constant, therefore
+    // we use appropriate conditions to differentiate between them
+    VK_HANGUL if is_korean => Key::Named(NamedKey::HangulMode),
+    VK_KANA if is_japanese => Key::Named(NamedKey::KanaMode),
+
+    VK_JUNJA => Key::Named(NamedKey::JunjaMode),
+    VK_FINAL => Key::Named(NamedKey::FinalMode),
+
+    // VK_HANJA and VK_KANJI are defined as the same constant, therefore
+    // we use appropriate conditions to differentiate between them
+    VK_HANJA if is_korean => Key::Named(NamedKey::HanjaMode),
+    VK_KANJI if is_japanese => Key::Named(NamedKey::KanjiMode),
+
+    VK_ESCAPE => Key::Named(NamedKey::Escape),
+    VK_CONVERT => Key::Named(NamedKey::Convert),
+    VK_NONCONVERT => Key::Named(NamedKey::NonConvert),
+    VK_ACCEPT => Key::Named(NamedKey::Accept),
+    VK_MODECHANGE => Key::Named(NamedKey::ModeChange),
+    VK_SPACE => Key::Character(" ".into()),
+    VK_PRIOR => Key::Named(NamedKey::PageUp),
+    VK_NEXT => Key::Named(NamedKey::PageDown),
+    VK_END => Key::Named(NamedKey::End),
+    VK_HOME => Key::Named(NamedKey::Home),
+    VK_LEFT => Key::Named(NamedKey::ArrowLeft),
+    VK_UP => Key::Named(NamedKey::ArrowUp),
+    VK_RIGHT => Key::Named(NamedKey::ArrowRight),
+    VK_DOWN => Key::Named(NamedKey::ArrowDown),
+    VK_SELECT => Key::Named(NamedKey::Select),
+    VK_PRINT => Key::Named(NamedKey::Print),
+    VK_EXECUTE => Key::Named(NamedKey::Execute),
+    VK_SNAPSHOT => Key::Named(NamedKey::PrintScreen),
+    VK_INSERT => Key::Named(NamedKey::Insert),
+    VK_DELETE => Key::Named(NamedKey::Delete),
+    VK_HELP => Key::Named(NamedKey::Help),
+    VK_LWIN => Key::Named(NamedKey::Meta),
+    VK_RWIN => Key::Named(NamedKey::Meta),
+    VK_APPS => Key::Named(NamedKey::ContextMenu),
+    VK_SLEEP => Key::Named(NamedKey::Standby),
+
+    // Numpad keys produce characters
+    VK_NUMPAD0 => Key::Unidentified(vkey),
+    VK_NUMPAD1 => Key::Unidentified(vkey),
+    VK_NUMPAD2 => Key::Unidentified(vkey),
+    VK_NUMPAD3 => Key::Unidentified(vkey),
+    VK_NUMPAD4 => Key::Unidentified(vkey),
+    VK_NUMPAD5 => Key::Unidentified(vkey),
+    VK_NUMPAD6 => Key::Unidentified(vkey),
+    VK_NUMPAD7 => Key::Unidentified(vkey),
+    VK_NUMPAD8 => Key::Unidentified(vkey),
+    VK_NUMPAD9 => Key::Unidentified(vkey),
+    VK_MULTIPLY => Key::Unidentified(vkey),
+    VK_ADD => Key::Unidentified(vkey),
+    VK_SEPARATOR => Key::Unidentified(vkey),
+    VK_SUBTRACT => Key::Unidentified(vkey),
+    VK_DECIMAL => Key::Unidentified(vkey),
+    VK_DIVIDE => Key::Unidentified(vkey),
+
+    VK_F1 => Key::Named(NamedKey::F1),
+    VK_F2 => Key::Named(NamedKey::F2),
+    VK_F3 => Key::Named(NamedKey::F3),
+    VK_F4 => Key::Named(NamedKey::F4),
+    VK_F5 => Key::Named(NamedKey::F5),
+    VK_F6 => Key::Named(NamedKey::F6),
+    VK_F7 => Key::Named(NamedKey::F7),
+    VK_F8 => Key::Named(NamedKey::F8),
+    VK_F9 => Key::Named(NamedKey::F9),
+    VK_F10 => Key::Named(NamedKey::F10),
+    VK_F11 => Key::Named(NamedKey::F11),
+    VK_F12 => Key::Named(NamedKey::F12),
+    VK_F13 => Key::Named(NamedKey::F13),
+    VK_F14 => Key::Named(NamedKey::F14),
+    VK_F15 => Key::Named(NamedKey::F15),
+    VK_F16 => Key::Named(NamedKey::F16),
+    VK_F17 => Key::Named(NamedKey::F17),
+    VK_F18 => Key::Named(NamedKey::F18),
+    VK_F19 => Key::Named(NamedKey::F19),
+    VK_F20 => Key::Named(NamedKey::F20),
+    VK_F21 => Key::Named(NamedKey::F21),
+    VK_F22 => Key::Named(NamedKey::F22),
+    VK_F23 => Key::Named(NamedKey::F23),
+    VK_F24 => Key::Named(NamedKey::F24),
+    VK_NAVIGATION_VIEW => Key::Unidentified(vkey),
+    VK_NAVIGATION_MENU => Key::Unidentified(vkey),
+    VK_NAVIGATION_UP => Key::Unidentified(vkey),
+    VK_NAVIGATION_DOWN => Key::Unidentified(vkey),
+    VK_NAVIGATION_LEFT => Key::Unidentified(vkey),
+    VK_NAVIGATION_RIGHT => Key::Unidentified(vkey),
+    VK_NAVIGATION_ACCEPT => Key::Unidentified(vkey),
+    VK_NAVIGATION_CANCEL => Key::Unidentified(vkey),
+    VK_NUMLOCK => Key::Named(NamedKey::NumLock),
+    VK_SCROLL => Key::Named(NamedKey::ScrollLock),
+    VK_OEM_NEC_EQUAL => Key::Unidentified(vkey),
+    // VK_OEM_FJ_JISHO => Key::Unidentified(native_code), // Conflicts with `VK_OEM_NEC_EQUAL`
+    VK_OEM_FJ_MASSHOU => Key::Unidentified(vkey),
+    VK_OEM_FJ_TOUROKU => Key::Unidentified(vkey),
+    VK_OEM_FJ_LOYA => Key::Unidentified(vkey),
+    VK_OEM_FJ_ROYA => Key::Unidentified(vkey),
+    VK_LSHIFT => Key::Named(NamedKey::Shift),
+    VK_RSHIFT => Key::Named(NamedKey::Shift),
+    VK_LCONTROL => Key::Named(NamedKey::Control),
+    VK_RCONTROL => Key::Named(NamedKey::Control),
+    VK_LMENU => Key::Named(NamedKey::Alt),
+    VK_RMENU => {
+      if has_alt_graph {
+        Key::Named(NamedKey::AltGraph)
+      } else {
+        Key::Named(NamedKey::Alt)
+      }
+    },
+    VK_BROWSER_BACK => Key::Named(NamedKey::BrowserBack),
+    VK_BROWSER_FORWARD => Key::Named(NamedKey::BrowserForward),
+    VK_BROWSER_REFRESH => Key::Named(NamedKey::BrowserRefresh),
+    VK_BROWSER_STOP => Key::Named(NamedKey::BrowserStop),
+    VK_BROWSER_SEARCH => Key::Named(NamedKey::BrowserSearch),
+    VK_BROWSER_FAVORITES => Key::Named(NamedKey::BrowserFavorites),
+    VK_BROWSER_HOME => Key::Named(NamedKey::BrowserHome),
+    VK_VOLUME_MUTE => Key::Named(NamedKey::AudioVolumeMute),
+    VK_VOLUME_DOWN => Key::Named(NamedKey::AudioVolumeDown),
+    VK_VOLUME_UP => Key::Named(NamedKey::AudioVolumeUp),
+    VK_MEDIA_NEXT_TRACK => Key::Named(NamedKey::MediaTrackNext),
+    VK_MEDIA_PREV_TRACK => Key::Named(NamedKey::MediaTrackPrevious),
+    VK_MEDIA_STOP => Key::Named(NamedKey::MediaStop),
+    VK_MEDIA_PLAY_PAUSE => Key::Named(NamedKey::MediaPlayPause),
+    VK_LAUNCH_MAIL => Key::Named(NamedKey::LaunchMail),
+    VK_LAUNCH_MEDIA_SELECT => Key::Named(NamedKey::LaunchMediaPlayer),
+    VK_LAUNCH_APP1 => Key::Named(NamedKey::LaunchApplication1),
+    VK_LAUNCH_APP2 => Key::Named(NamedKey::LaunchApplication2),
+
+    // This function only converts "non-printable"
+    VK_OEM_1 => Key::Unidentified(vkey),
+    VK_OEM_PLUS => Key::Unidentified(vkey),
+    VK_OEM_COMMA => Key::Unidentified(vkey),
+    VK_OEM_MINUS => Key::Unidentified(vkey),
+    VK_OEM_PERIOD => Key::Unidentified(vkey),
+    VK_OEM_2 => Key::Unidentified(vkey),
+    VK_OEM_3 => Key::Unidentified(vkey),
+
+    VK_GAMEPAD_A => Key::Unidentified(vkey),
+    VK_GAMEPAD_B => Key::Unidentified(vkey),
+    VK_GAMEPAD_X => Key::Unidentified(vkey),
+    VK_GAMEPAD_Y => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_SHOULDER => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_SHOULDER => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_TRIGGER => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_TRIGGER => Key::Unidentified(vkey),
+    VK_GAMEPAD_DPAD_UP => Key::Unidentified(vkey),
+    VK_GAMEPAD_DPAD_DOWN => Key::Unidentified(vkey),
+    VK_GAMEPAD_DPAD_LEFT => Key::Unidentified(vkey),
+    VK_GAMEPAD_DPAD_RIGHT => Key::Unidentified(vkey),
+    VK_GAMEPAD_MENU => Key::Unidentified(vkey),
+    VK_GAMEPAD_VIEW => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_THUMBSTICK_BUTTON => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_THUMBSTICK_BUTTON => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_THUMBSTICK_UP => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_THUMBSTICK_DOWN => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_THUMBSTICK_RIGHT => Key::Unidentified(vkey),
+    VK_GAMEPAD_LEFT_THUMBSTICK_LEFT => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_THUMBSTICK_UP => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_THUMBSTICK_DOWN => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_THUMBSTICK_RIGHT => Key::Unidentified(vkey),
+    VK_GAMEPAD_RIGHT_THUMBSTICK_LEFT => Key::Unidentified(vkey),
+
+    // This function only converts "non-printable"
+    VK_OEM_4 => Key::Unidentified(vkey),
+    VK_OEM_5 => Key::Unidentified(vkey),
+    VK_OEM_6 => Key::Unidentified(vkey),
+    VK_OEM_7 => Key::Unidentified(vkey),
+    VK_OEM_8 => Key::Unidentified(vkey),
+    VK_OEM_AX => Key::Unidentified(vkey),
+    VK_OEM_102 => Key::Unidentified(vkey),
+
+    VK_ICO_HELP => Key::Unidentified(vkey),
+    VK_ICO_00 => Key::Unidentified(vkey),
+
+    VK_PROCESSKEY => Key::Named(NamedKey::Process),
+
+    VK_ICO_CLEAR => Key::Unidentified(vkey),
+    VK_PACKET => Key::Unidentified(vkey),
+    VK_OEM_RESET => Key::Unidentified(vkey),
+    VK_OEM_JUMP => Key::Unidentified(vkey),
+    VK_OEM_PA1 => Key::Unidentified(vkey),
+    VK_OEM_PA2 => Key::Unidentified(vkey),
+    VK_OEM_PA3 => Key::Unidentified(vkey),
+    VK_OEM_WSCTRL => Key::Unidentified(vkey),
+    VK_OEM_CUSEL => Key::Unidentified(vkey),
+
+    VK_OEM_ATTN => Key::Named(NamedKey::Attn),
+    VK_OEM_FINISH => {
+      if is_japanese {
+        Key::Named(NamedKey::Katakana)
+      } else {
+        // This matches IE and Firefox behaviour according to
+        // https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key/Key_Values
+        // At the time of writing, there is no `NamedKey::Finish` variant as
+        // Finish is not mentioned at https://w3c.github.io/uievents-key/
+        // Also see: https://github.com/pyfisch/keyboard-types/issues/9
+        Key::Unidentified(vkey)
+      }
+    },
+    VK_OEM_COPY => Key::Named(NamedKey::Copy),
+    VK_OEM_AUTO => Key::Named(NamedKey::Hankaku),
+    VK_OEM_ENLW => Key::Named(NamedKey::Zenkaku),
+    VK_OEM_BACKTAB => Key::Named(NamedKey::Romaji),
+    VK_ATTN => Key::Named(NamedKey::KanaMode),
+    VK_CRSEL => Key::Named(NamedKey::CrSel),
+    VK_EXSEL => Key::Named(NamedKey::ExSel),
+    VK_EREOF => Key::Named(NamedKey::EraseEof),
+    VK_PLAY => Key::Named(NamedKey::Play),
+    VK_ZOOM => Key::Named(NamedKey::ZoomToggle),
+    VK_NONAME => Key::Unidentified(vkey),
+    VK_PA1 => Key::Unidentified(vkey),
+    VK_OEM_CLEAR => Key::Named(NamedKey::Clear),
+    _ => Key::Unidentified(vkey),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::input::scancode::code_to_scancode_for,
+    windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+      VK_LCONTROL,
+      VK_LMENU,
+      VK_LSHIFT,
+      VK_OEM_6,
+      VK_OEM_7,
+    },
+  };
+
+  fn layout(snapshot: &str) -> Layout {
+    Layout::from_snapshot(snapshot).unwrap()
+  }
+
+  /// Presses the key at `code` while `held` are down, the way Windows reports it.
+  fn press(layout: &Layout, code: Code, vkey: VIRTUAL_KEY, held: &[VIRTUAL_KEY]) -> KeyEvent {
+    let mut kbd_state = RawKeyInput::EMPTY_KBD_STATE;
+    for &held in held {
+      kbd_state[held as usize] |= 0x80;
+      // The side-agnostic virtual key is held along with either side.
+      let agnostic = match held {
+        VK_LSHIFT | VK_RSHIFT => VK_SHIFT,
+        VK_LCONTROL | VK_RCONTROL => VK_CONTROL,
+        VK_LMENU | VK_RMENU => VK_MENU,
+        _ => continue,
+      };
+      kbd_state[agnostic as usize] |= 0x80;
+    }
+    let input = RawKeyInput {
+      vkey,
+      scancode: code_to_scancode_for(code, false).unwrap() as ExScancode,
+      is_pressed: true,
+      is_repeat: false,
+      kbd_state,
+    };
+    KeyEvent::translate(layout, &input)
+  }
+
+  fn character(text: &str) -> Key {
+    Key::Character(text.to_string())
+  }
+
+  /// AltGr as Windows reports it, with left control held along with right alt.
+  const ALT_GRAPH: &[VIRTUAL_KEY] = &[VK_LCONTROL, VK_RMENU];
+
+  #[test]
+  fn every_fixture_parses_and_round_trips() {
+    for (name, snapshot) in fixtures::ALL {
+      let layout = Layout::from_snapshot(snapshot).unwrap_or_else(|error| panic!("{name}: {error}"));
+      let again = Layout::from_snapshot(&layout.to_snapshot()).unwrap();
+      assert_eq!(again.to_snapshot(), layout.to_snapshot(), "{name}");
+    }
+  }
+
+  #[test]
+  fn plain_and_shifted_characters() {
+    let expected = [
+      (fixtures::US, Code::KeyQ, "q", "Q"),
+      (fixtures::US, Code::Digit2, "2", "@"),
+      (fixtures::US_INTERNATIONAL, Code::Digit2, "2", "@"),
+      (fixtures::GERMAN, Code::KeyQ, "q", "Q"),
+      (fixtures::GERMAN, Code::Minus, "ß", "?"),
+      (fixtures::FRENCH, Code::KeyQ, "a", "A"),
+    ];
+    for (snapshot, code, plain, shifted) in expected {
+      let layout = layout(snapshot);
+      let vkey = keycode_to_vkey(code, layout.hkl);
+      let event = press(&layout, code, vkey, &[]);
+      assert_eq!(event.key, character(plain), "{code}");
+      assert_eq!(event.key_without_modifiers, character(plain), "{code}");
+      assert_eq!(event.modifiers, Modifiers::empty());
+
+      let event = press(&layout, code, vkey, &[VK_LSHIFT]);
+      assert_eq!(event.key, character(shifted), "{code}");
+      assert_eq!(event.key_without_modifiers, character(plain), "{code}");
+      assert_eq!(event.modifiers, Modifiers::SHIFT);
+      assert_eq!(event.modifier_state, ModifierState::LeftShift);
+    }
+  }
+
+  #[test]
+  fn dead_keys_are_reported_without_composing() {
+    let german = layout(fixtures::GERMAN);
+    let event = press(&german, Code::Equal, VK_OEM_6, &[]);
+    assert_eq!(event.key, Key::Dead(Some('´')));
+    // Bindings see the dead key as the character it stands for.
+    assert_eq!(event.key_without_modifiers, character("´"));
+    assert_eq!(press(&german, Code::Equal, VK_OEM_6, &[VK_LSHIFT]).key, Key::Dead(Some('`')));
+    // The key after a dead key is reported as itself; the composed text comes with `Char`.
+    assert_eq!(press(&german, Code::KeyE, b'E' as VIRTUAL_KEY, &[]).key, character("e"));
+
+    let international = layout(fixtures::US_INTERNATIONAL);
+    let event = press(&international, Code::Quote, VK_OEM_7, &[]);
+    assert_eq!(event.key, Key::Dead(Some('\'')));
+    assert_eq!(event.key_without_modifiers, character("'"));
+    assert_eq!(press(&international, Code::Quote, VK_OEM_7, &[VK_LSHIFT]).key, Key::Dead(Some('"')));
+    // Space is reported as itself too, although it types the accent alone after a dead key.
+    assert_eq!(press(&international, Code::Space, VK_SPACE, &[]).key, character(" "));
+  }
+
+  #[test]
+  fn alt_graph_selects_the_third_level() {
+    let expected = [
+      (fixtures::GERMAN, Code::KeyQ, Key::Character("@".to_string()), "q"),
+      (fixtures::GERMAN, Code::KeyE, Key::Character("€".to_string()), "e"),
+      (fixtures::GERMAN, Code::Digit7, Key::Character("{".to_string()), "7"),
+      (fixtures::FRENCH, Code::Digit0, Key::Character("@".to_string()), "à"),
+      (fixtures::FRENCH, Code::Digit2, Key::Dead(Some('~')), "é"),
+      (fixtures::US_INTERNATIONAL, Code::KeyQ, Key::Character("ä".to_string()), "q"),
+    ];
+    for (snapshot, code, key, plain) in expected {
+      let layout = layout(snapshot);
+      let event = press(&layout, code, keycode_to_vkey(code, layout.hkl), ALT_GRAPH);
+      assert_eq!(event.key, key, "{code}");
+      assert_eq!(event.key_without_modifiers, character(plain), "{code}");
+      // AltGr is neither control nor alt to bindings.
+      assert_eq!(event.modifiers, Modifiers::empty(), "{code}");
+      assert_eq!(event.modifier_state, ModifierState::AltGraph, "{code}");
+    }
+  }
+
+  #[test]
+  fn control_alt_acts_as_alt_graph_for_characters() {
+    // Windows treats left control and left alt together like AltGr when looking up characters, but
+    // bindings still see both modifiers.
+    let german = layout(fixtures::GERMAN);
+    let event = press(&german, Code::KeyQ, b'Q' as VIRTUAL_KEY, &[VK_LCONTROL, VK_LMENU]);
+    assert_eq!(event.key, character("@"));
+    assert_eq!(event.key_without_modifiers, character("q"));
+    assert_eq!(event.modifiers, Modifiers::CONTROL | Modifiers::ALT);
+    assert_eq!(event.modifier_state, ModifierState::LeftControl | ModifierState::LeftAlt);
+
+    // Layouts without a third level have nothing to produce.
+    let us = layout(fixtures::US);
+    let event = press(&us, Code::KeyQ, b'Q' as VIRTUAL_KEY, &[VK_LCONTROL, VK_LMENU]);
+    assert_eq!(event.key, Key::Unidentified(b'Q' as VIRTUAL_KEY));
+    assert_eq!(event.key_without_modifiers, character("q"));
+    assert_eq!(event.modifiers, Modifiers::CONTROL | Modifiers::ALT);
+  }
+
+  #[test]
+  fn mouse_flags_decide_shift_and_control() {
+    use windows_sys::Win32::System::SystemServices::MK_LBUTTON;
+
+    let mut kbd_state = RawKeyInput::EMPTY_KBD_STATE;
+    for vkey in [VK_RSHIFT, VK_SHIFT, VK_LMENU, VK_MENU] {
+      kbd_state[vkey as usize] |= 0x80;
+    }
+    // The side comes from the keyboard state, and alt from nowhere else.
+    let state = ModifierState::from_mouse_flags(MK_LBUTTON | MK_SHIFT, &kbd_state, false);
+    assert_eq!(state, ModifierState::RightShift | ModifierState::LeftAlt);
+    // Released by the time of the message, even though the keyboard state is a step ahead.
+    assert_eq!(ModifierState::from_mouse_flags(MK_LBUTTON, &kbd_state, false), ModifierState::LeftAlt);
+    // Held according to the message only.
+    let state = ModifierState::from_mouse_flags(MK_CONTROL, &RawKeyInput::EMPTY_KBD_STATE, false);
+    assert_eq!(state, ModifierState::LeftControl);
+
+    // AltGr sets `MK_CONTROL` through the left control it holds, which isn't reported on its own.
+    let mut kbd_state = RawKeyInput::EMPTY_KBD_STATE;
+    for &vkey in ALT_GRAPH.iter().chain(&[VK_CONTROL, VK_MENU]) {
+      kbd_state[vkey as usize] |= 0x80;
+    }
+    assert_eq!(ModifierState::from_mouse_flags(MK_CONTROL, &kbd_state, true), ModifierState::AltGraph);
+    let state = ModifierState::from_mouse_flags(MK_CONTROL, &kbd_state, false);
+    assert_eq!(state, ModifierState::LeftControl | ModifierState::RightAlt);
+  }
+
+  #[test]
+  fn japanese_keys_produce_their_characters() {
+    let japanese = layout(fixtures::JAPANESE);
+    let expected = [
+      (Code::Digit2, "2", "\""),
+      (Code::Equal, "^", "~"),
+      (Code::BracketLeft, "@", "`"),
+      (Code::Quote, ":", "*"),
+      (Code::IntlYen, "\\", "|"),
+      (Code::IntlRo, "\\", "_"),
+    ];
+    for (code, plain, shifted) in expected {
+      let vkey = keycode_to_vkey(code, japanese.hkl);
+      assert_eq!(press(&japanese, code, vkey, &[]).key, character(plain), "{code}");
+      let event = press(&japanese, code, vkey, &[VK_LSHIFT]);
+      assert_eq!(event.key, character(shifted), "{code}");
+      assert_eq!(event.key_without_modifiers, character(plain), "{code}");
+    }
+  }
+
+  #[test]
+  fn japanese_input_method_keys_are_named() {
+    let japanese = layout(fixtures::JAPANESE);
+    let us = layout(fixtures::US);
+    // The same virtual keys mean something else to Korean layouts, and nothing to the rest, which fall
+    // back to whatever character the physical key has.
+    let expected = [
+      (Code::KanaMode, VK_KANA, NamedKey::KanaMode, Key::Unidentified(VK_KANA)),
+      (Code::Backquote, VK_KANJI, NamedKey::KanjiMode, character("`")),
+      (Code::Lang3, VK_OEM_FINISH, NamedKey::Katakana, Key::Unidentified(VK_OEM_FINISH)),
+    ];
+    for (code, vkey, named, elsewhere) in expected {
+      assert_eq!(press(&japanese, code, vkey, &[]).key, Key::Named(named), "{code}");
+      assert_eq!(press(&us, code, vkey, &[]).key, elsewhere, "{code}");
+    }
+    // Not tied to the layout.
+    assert_eq!(press(&japanese, Code::Backquote, VK_OEM_AUTO, &[]).key, Key::Named(NamedKey::Hankaku));
+    assert_eq!(press(&japanese, Code::Backquote, VK_OEM_ENLW, &[]).key, Key::Named(NamedKey::Zenkaku));
+    assert_eq!(press(&japanese, Code::Convert, VK_CONVERT, &[]).key, Key::Named(NamedKey::Convert));
+    assert_eq!(press(&japanese, Code::NonConvert, VK_NONCONVERT, &[]).key, Key::Named(NamedKey::NonConvert));
+  }
+
+  #[test]
+  fn japanese_language_codes_resolve_through_their_virtual_keys() {
+    let japanese = layout(fixtures::JAPANESE);
+    let us = layout(fixtures::US);
+    let no_mods = WindowsModifiers::empty();
+    let expected = [
+      (Code::Lang1, Key::Named(NamedKey::KanaMode)),
+      (Code::KanaMode, Key::Named(NamedKey::KanaMode)),
+      (Code::Lang3, Key::Named(NamedKey::Katakana)),
+      // Japanese keyboards have no virtual keys of their own for these.
+      (Code::Lang2, Key::Unidentified(0)),
+      (Code::Lang4, Key::Unidentified(0)),
+      (Code::Lang5, Key::Unidentified(0)),
+    ];
+    for (code, key) in expected {
+      assert_eq!(japanese.get_key_for_code(no_mods, code), key, "{code}");
+    }
+    for code in [Code::Lang1, Code::Lang3] {
+      assert_eq!(us.get_key_for_code(no_mods, code), Key::Unidentified(0), "{code}");
+    }
+    // Character keys come from the layout, not the virtual key.
+    assert_eq!(japanese.get_key_for_code(no_mods, Code::Quote), character(":"));
+  }
+
+  #[test]
+  fn control_alone_keeps_the_unmodified_character() {
+    let french = layout(fixtures::FRENCH);
+    let event = press(&french, Code::KeyQ, b'A' as VIRTUAL_KEY, &[VK_LCONTROL]);
+    assert_eq!(event.key, character("a"));
+    assert_eq!(event.modifiers, Modifiers::CONTROL);
+
+    // Control turns NumLock into Pause on Windows, which is undone to match the physical key.
+    let event = press(&french, Code::NumLock, VK_PAUSE, &[VK_LCONTROL]);
+    assert_eq!(event.key, Key::Named(NamedKey::NumLock));
+    assert_eq!(event.key_without_modifiers, Key::Named(NamedKey::NumLock));
+  }
+}
@@ -0,0 +1,24 @@
+//!
+//! Snapshots of common layouts in the format read by [`Layout::from_snapshot`](super::Layout::from_snapshot).
+//!
+//! These are written by hand from the published layout tables rather than dumped from an installed
+//! layout, so they only cover the keys that produce characters, and none of them has been checked
+//! against what Windows itself reports yet. A full snapshot of an installed layout can be taken with
+//! `KeyboardLayout::current().with_layout(Layout::to_snapshot)`, and should replace the one here when
+//! they disagree.
+//!
+
+pub const US: &str = include_str!("fixtures/us.layout");
+pub const US_INTERNATIONAL: &str = include_str!("fixtures/us_international.layout");
+pub const GERMAN: &str = include_str!("fixtures/german.layout");
+pub const FRENCH: &str = include_str!("fixtures/french.layout");
+pub const JAPANESE: &str = include_str!("fixtures/japanese.layout");
+
+/// Every fixture along with a short name, in the order above.
+pub const ALL: [(&str, &str); 5] = [
+  ("us", US),
+  ("us_international", US_INTERNATIONAL),
+  ("german", GERMAN),
+  ("french", FRENCH),
+  ("japanese", JAPANESE),
+];
@@ -1,6 +1,14 @@
 use {
+  super::{
+    keyboard::{
+      get_kbd_state,
+      layout::LayoutCache,
+    },
+    keymap::ModifierState,
+  },
   crate::{
     high_word,
+    low_word,
     signed_high_word,
     signed_low_word,
     user::{
@@ -9,7 +17,6 @@ use {
     },
   },
   dpi::PhysicalPosition,
-  keyboard_types::Modifiers,
   pointer_types::{
     ButtonState,
     mouse::{
@@ -29,6 +36,14 @@ use {
   },
 };
 
+/// The modifiers held when a mouse message with `w_param` was sent. See
+/// [`ModifierState::from_mouse_flags`]. `GetKeyboardState` keeps up with the messages the thread has
+/// taken from its queue rather than the keyboard itself, so alt is as it was for this message too.
+pub(crate) fn mouse_modifier_state(w_param: WParam) -> ModifierState {
+  let has_alt_graph = LayoutCache::lock().get_current_layout().1.has_alt_graph;
+  ModifierState::from_mouse_flags(low_word(w_param.0 as u32) as u32, &get_kbd_state(), has_alt_graph)
+}
+
 pub(crate) fn mouse_event(message: u32, w_param: WParam, l_param: LParam) -> MouseEvent {
   let flags = w_param.0 as u32;
 
@@ -96,7 +111,7 @@ pub(crate) fn mouse_event(message: u32, w_param: WParam, l_param: LParam) -> Mou
     position,
     state,
     button,
-    modifiers: Modifiers::empty(), // TODO: Get modifiers from w_param
+    modifiers: mouse_modifier_state(w_param).to_modifiers(),
     is_double_click,
   }
 }
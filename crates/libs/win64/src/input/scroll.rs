@@ -0,0 +1,301 @@
+//!
+//! Turning mouse wheel deltas into scroll amounts.
+//!
+//! Wheel messages report movement in units where one notch of a regular wheel is [`WHEEL_DELTA`].
+//! Precision touchpads and free-spinning wheels send smaller deltas, so they have to be accumulated
+//! until they add up to something worth scrolling.
+//!
+
+/// The delta of one notch of a regular mouse wheel.
+pub const WHEEL_DELTA: i32 = 120;
+
+/// `SPI_GETWHEELSCROLLLINES` reports this when the wheel is set to scroll a page at a time.
+pub const WHEEL_PAGESCROLL: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollAxis {
+  Vertical,
+  Horizontal,
+}
+
+/// How far one notch of the wheel scrolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WheelScroll {
+  /// Lines for the vertical wheel, characters for the horizontal one. `Lines(0)` disables scrolling.
+  Lines(u32),
+  Page,
+}
+
+impl WheelScroll {
+  pub const fn from_raw(value: u32) -> Self {
+    match value {
+      WHEEL_PAGESCROLL => Self::Page,
+      lines => Self::Lines(lines),
+    }
+  }
+
+  pub const fn to_raw(self) -> u32 {
+    match self {
+      Self::Lines(lines) => lines,
+      Self::Page => WHEEL_PAGESCROLL,
+    }
+  }
+}
+
+/// The wheel settings from the mouse control panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScrollConfig {
+  /// `SPI_GETWHEELSCROLLLINES`
+  pub vertical: WheelScroll,
+  /// `SPI_GETWHEELSCROLLCHARS`
+  pub horizontal_chars: u32,
+}
+
+impl Default for ScrollConfig {
+  /// The Windows defaults of three lines and three characters per notch.
+  fn default() -> Self {
+    Self { vertical: WheelScroll::Lines(3), horizontal_chars: 3 }
+  }
+}
+
+impl ScrollConfig {
+  pub const fn per_notch(&self, axis: ScrollAxis) -> WheelScroll {
+    match axis {
+      ScrollAxis::Vertical => self.vertical,
+      ScrollAxis::Horizontal => WheelScroll::Lines(self.horizontal_chars),
+    }
+  }
+}
+
+/// Positive amounts scroll up (away from the user) or right, matching the sign of the wheel delta.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum ScrollAmount {
+  Lines(i32),
+  Pages(i32),
+}
+
+impl ScrollAmount {
+  pub const fn is_zero(&self) -> bool {
+    matches!(self, Self::Lines(0) | Self::Pages(0))
+  }
+}
+
+/// Collects wheel deltas until they add up to whole lines or pages.
+///
+/// Keep one per window. Whatever is left over is thrown away when the wheel changes direction, so
+/// reversing never has to first cancel out the remainder of the previous direction.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WheelAccumulator {
+  vertical: Pending,
+  horizontal: Pending,
+}
+
+/// Delta collected along one axis, in `1 / scale` units of delta.
+///
+/// A line is rarely a whole number of delta units when the line count doesn't divide [`WHEEL_DELTA`], so
+/// the delta is kept multiplied by the line count. Whatever is left over after taking out whole lines is
+/// then exact, and one notch always scrolls exactly the line count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Pending {
+  amount: i64,
+  scale: i64,
+}
+
+impl Default for Pending {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Pending {
+  const fn new() -> Self {
+    Self { amount: 0, scale: 1 }
+  }
+
+  const fn delta(&self) -> f64 {
+    self.amount as f64 / self.scale as f64
+  }
+
+  /// Adds `delta`, first converting what is pending to `scale` if it was kept in other units.
+  fn add(&mut self, delta: i32, scale: i64) {
+    if scale != self.scale {
+      self.amount = self.amount * scale / self.scale;
+      self.scale = scale;
+    }
+    let delta = delta as i64 * scale;
+    if self.amount.signum() * delta.signum() < 0 {
+      self.amount = 0;
+    }
+    self.amount += delta;
+  }
+}
+
+impl WheelAccumulator {
+  pub const fn new() -> Self {
+    Self { vertical: Pending::new(), horizontal: Pending::new() }
+  }
+
+  /// The delta collected so far that hasn't been turned into scrolling yet. Can be a fraction of a unit
+  /// when the last scroll took out whole lines.
+  pub const fn pending(&self, axis: ScrollAxis) -> f64 {
+    match axis {
+      ScrollAxis::Vertical => self.vertical.delta(),
+      ScrollAxis::Horizontal => self.horizontal.delta(),
+    }
+  }
+
+  /// Throws away whatever has been collected, e.g. when the window loses focus.
+  pub fn reset(&mut self) {
+    *self = Self::new();
+  }
+
+  /// Adds `delta` and returns everything collected so far.
+  pub fn accumulate(&mut self, axis: ScrollAxis, delta: i32) -> f64 {
+    let pending = self.pending_mut(axis);
+    pending.add(delta, pending.scale);
+    pending.delta()
+  }
+
+  /// Adds `delta` and takes out as many whole lines or pages as have been collected. The rest is
+  /// kept for the next message.
+  pub fn scroll(&mut self, axis: ScrollAxis, delta: i32, per_notch: WheelScroll) -> ScrollAmount {
+    let pending = self.pending_mut(axis);
+    let lines = match per_notch {
+      WheelScroll::Lines(0) => {
+        *pending = Pending::new();
+        return ScrollAmount::Lines(0);
+      },
+      WheelScroll::Lines(lines) => lines as i64,
+      WheelScroll::Page => 1,
+    };
+
+    // In delta units times `lines`, a notch is `WHEEL_DELTA` lines (or pages) long.
+    pending.add(delta, lines);
+    let notch = WHEEL_DELTA as i64;
+    let whole = (pending.amount / notch).clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+    pending.amount %= notch;
+    match per_notch {
+      WheelScroll::Page => ScrollAmount::Pages(whole),
+      WheelScroll::Lines(_) => ScrollAmount::Lines(whole),
+    }
+  }
+
+  /// Adds `delta` and converts all of it into pixels, for views that scroll smoothly. `line_size`
+  /// and `page_size` are the pixel extents of a line (or character) and a page along `axis`.
+  pub fn scroll_pixels(
+    &mut self,
+    axis: ScrollAxis,
+    delta: i32,
+    per_notch: WheelScroll,
+    line_size: f64,
+    page_size: f64,
+  ) -> f64 {
+    let pending = self.accumulate(axis, delta);
+    *self.pending_mut(axis) = Pending::new();
+
+    let notches = pending / WHEEL_DELTA as f64;
+    match per_notch {
+      WheelScroll::Lines(lines) => notches * lines as f64 * line_size,
+      WheelScroll::Page => notches * page_size,
+    }
+  }
+
+  fn pending_mut(&mut self, axis: ScrollAxis) -> &mut Pending {
+    match axis {
+      ScrollAxis::Vertical => &mut self.vertical,
+      ScrollAxis::Horizontal => &mut self.horizontal,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const AXIS: ScrollAxis = ScrollAxis::Vertical;
+
+  #[test]
+  fn a_notch_scrolls_exactly_the_line_count() {
+    for lines in 1..=100 {
+      let mut wheel = WheelAccumulator::new();
+      for _ in 0..5 {
+        assert_eq!(
+          wheel.scroll(AXIS, WHEEL_DELTA, WheelScroll::Lines(lines)),
+          ScrollAmount::Lines(lines as i32)
+        );
+        assert_eq!(wheel.pending(AXIS), 0.0);
+      }
+      assert_eq!(
+        wheel.scroll(AXIS, -WHEEL_DELTA, WheelScroll::Lines(lines)),
+        ScrollAmount::Lines(-(lines as i32))
+      );
+    }
+  }
+
+  #[test]
+  fn precise_deltas_add_up_to_whole_notches() {
+    for (lines, step) in [(3, 10), (7, 1), (29, 8), (100, 24)] {
+      let mut wheel = WheelAccumulator::new();
+      let mut total = 0;
+      for _ in 0..(WHEEL_DELTA / step) * 4 {
+        let ScrollAmount::Lines(scrolled) = wheel.scroll(AXIS, step, WheelScroll::Lines(lines)) else {
+          panic!("lines were asked for");
+        };
+        total += scrolled;
+      }
+      assert_eq!(total, lines as i32 * 4, "{lines} lines in steps of {step}");
+      assert_eq!(wheel.pending(AXIS), 0.0);
+    }
+  }
+
+  #[test]
+  fn changing_direction_drops_the_remainder() {
+    let mut wheel = WheelAccumulator::new();
+    assert_eq!(wheel.scroll(AXIS, 39, WheelScroll::Lines(3)), ScrollAmount::Lines(0));
+    // Would need the 39 cancelled out first if the remainder were kept.
+    assert_eq!(wheel.scroll(AXIS, -40, WheelScroll::Lines(3)), ScrollAmount::Lines(-1));
+    assert_eq!(wheel.pending(AXIS), 0.0);
+  }
+
+  #[test]
+  fn axes_are_kept_apart() {
+    let mut wheel = WheelAccumulator::new();
+    wheel.accumulate(ScrollAxis::Horizontal, 60);
+    assert_eq!(wheel.scroll(AXIS, 60, WheelScroll::Lines(1)), ScrollAmount::Lines(0));
+    assert_eq!(wheel.scroll(ScrollAxis::Horizontal, 60, WheelScroll::Lines(1)), ScrollAmount::Lines(1));
+  }
+
+  #[test]
+  fn zero_lines_never_scrolls() {
+    let mut wheel = WheelAccumulator::new();
+    for _ in 0..3 {
+      assert_eq!(wheel.scroll(AXIS, WHEEL_DELTA, WheelScroll::Lines(0)), ScrollAmount::Lines(0));
+      assert_eq!(wheel.pending(AXIS), 0.0);
+    }
+  }
+
+  #[test]
+  fn pages_scroll_a_notch_at_a_time() {
+    let mut wheel = WheelAccumulator::new();
+    assert_eq!(wheel.scroll(AXIS, 60, WheelScroll::Page), ScrollAmount::Pages(0));
+    assert_eq!(wheel.scroll(AXIS, 60, WheelScroll::Page), ScrollAmount::Pages(1));
+    assert_eq!(wheel.scroll(AXIS, WHEEL_DELTA * 2, WheelScroll::Page), ScrollAmount::Pages(2));
+    assert_eq!(wheel.scroll(AXIS, -WHEEL_DELTA, WheelScroll::Page), ScrollAmount::Pages(-1));
+  }
+
+  #[test]
+  fn pixels_take_all_of_the_delta() {
+    let mut wheel = WheelAccumulator::new();
+    assert_eq!(wheel.scroll_pixels(AXIS, WHEEL_DELTA, WheelScroll::Lines(3), 10.0, 500.0), 30.0);
+    assert_eq!(wheel.scroll_pixels(AXIS, 60, WheelScroll::Lines(3), 10.0, 500.0), 15.0);
+    assert_eq!(wheel.pending(AXIS), 0.0);
+    assert_eq!(wheel.scroll_pixels(AXIS, -240, WheelScroll::Page, 10.0, 500.0), -1000.0);
+  }
+
+  #[test]
+  fn pixels_include_what_lines_left_over() {
+    let mut wheel = WheelAccumulator::new();
+    assert_eq!(wheel.scroll(AXIS, 50, WheelScroll::Lines(3)), ScrollAmount::Lines(1));
+    assert_eq!(wheel.scroll_pixels(AXIS, 0, WheelScroll::Lines(3), 12.0, 500.0), 3.0);
+  }
+}
@@ -0,0 +1,311 @@
+//!
+//! Synthesized input through `SendInput`.
+//!
+//! Inputs are described with plain data first and only turned into `INPUT` structs when sent, so the
+//! sequences can be built and inspected without touching the input stream. Building them doesn't call
+//! into Win32, so unlike sending them it works on every target.
+//!
+
+#[cfg(target_os = "windows")]
+mod inject;
+#[cfg(target_os = "windows")]
+pub use inject::*;
+use {
+  crate::Rect,
+  bitflags::bitflags,
+  dpi::PhysicalPosition,
+  keyboard_types::KeyState,
+  pointer_types::{
+    ButtonState,
+    mouse::MouseButton,
+  },
+  windows_sys::Win32::UI::{
+    Input::KeyboardAndMouse::{
+      self,
+      INPUT,
+      INPUT_0,
+      KEYBD_EVENT_FLAGS,
+      KEYBDINPUT,
+      MOUSE_EVENT_FLAGS,
+      MOUSEINPUT,
+      VIRTUAL_KEY,
+    },
+    WindowsAndMessaging,
+  },
+};
+
+bitflags! {
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  pub struct KeyboardInputFlags: u32 {
+    const ExtendedKey = KeyboardAndMouse::KEYEVENTF_EXTENDEDKEY;
+    const KeyUp = KeyboardAndMouse::KEYEVENTF_KEYUP;
+    const Scancode = KeyboardAndMouse::KEYEVENTF_SCANCODE;
+    const Unicode = KeyboardAndMouse::KEYEVENTF_UNICODE;
+  }
+}
+
+impl KeyboardInputFlags {
+  #[inline]
+  pub const fn to_raw(self) -> KEYBD_EVENT_FLAGS {
+    self.bits()
+  }
+}
+
+bitflags! {
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  pub struct MouseInputFlags: u32 {
+    const Absolute = KeyboardAndMouse::MOUSEEVENTF_ABSOLUTE;
+    const HorizontalWheel = KeyboardAndMouse::MOUSEEVENTF_HWHEEL;
+    const LeftDown = KeyboardAndMouse::MOUSEEVENTF_LEFTDOWN;
+    const LeftUp = KeyboardAndMouse::MOUSEEVENTF_LEFTUP;
+    const MiddleDown = KeyboardAndMouse::MOUSEEVENTF_MIDDLEDOWN;
+    const MiddleUp = KeyboardAndMouse::MOUSEEVENTF_MIDDLEUP;
+    const Move = KeyboardAndMouse::MOUSEEVENTF_MOVE;
+    const MoveNoCoalesce = KeyboardAndMouse::MOUSEEVENTF_MOVE_NOCOALESCE;
+    const RightDown = KeyboardAndMouse::MOUSEEVENTF_RIGHTDOWN;
+    const RightUp = KeyboardAndMouse::MOUSEEVENTF_RIGHTUP;
+    const VirtualDesk = KeyboardAndMouse::MOUSEEVENTF_VIRTUALDESK;
+    const Wheel = KeyboardAndMouse::MOUSEEVENTF_WHEEL;
+    const XDown = KeyboardAndMouse::MOUSEEVENTF_XDOWN;
+    const XUp = KeyboardAndMouse::MOUSEEVENTF_XUP;
+  }
+}
+
+impl MouseInputFlags {
+  #[inline]
+  pub const fn to_raw(self) -> MOUSE_EVENT_FLAGS {
+    self.bits()
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyboardInput {
+  pub vkey: VIRTUAL_KEY,
+  pub scancode: u16,
+  pub flags: KeyboardInputFlags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MouseInput {
+  pub dx: i32,
+  pub dy: i32,
+  /// Wheel delta for wheel events, `XBUTTON1`/`XBUTTON2` for x button events.
+  pub data: i32,
+  pub flags: MouseInputFlags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Input {
+  Keyboard(KeyboardInput),
+  Mouse(MouseInput),
+}
+
+/// Largest coordinate of the normalized space used by absolute mouse moves.
+pub const ABSOLUTE_MAX: i32 = 65535;
+
+/// Maps a position on the virtual desktop into the normalized `0..=65535` space of absolute mouse moves.
+pub fn normalize_absolute(position: PhysicalPosition<i32>, desktop: Rect) -> (i32, i32) {
+  fn normalize(value: i32, start: i32, end: i32) -> i32 {
+    let extent = (end - start - 1).max(1) as i64;
+    let offset = (value - start).clamp(0, extent as i32) as i64;
+    ((offset * ABSOLUTE_MAX as i64 + extent / 2) / extent) as i32
+  }
+
+  (normalize(position.x, desktop.left, desktop.right), normalize(position.y, desktop.top, desktop.bottom))
+}
+
+impl Input {
+  /// A key event for an extended scancode, such as `0xe04b` for the left arrow, which is independent of
+  /// the active keyboard layout.
+  pub fn scancode(scancode: u32, state: KeyState) -> Self {
+    let mut flags = KeyboardInputFlags::Scancode;
+    flags.set(KeyboardInputFlags::ExtendedKey, (scancode & 0xe000) == 0xe000);
+    flags.set(KeyboardInputFlags::KeyUp, matches!(state, KeyState::Up));
+    Self::Keyboard(KeyboardInput { vkey: 0, scancode: (scancode & 0xff) as u16, flags })
+  }
+
+  /// A single UTF-16 code unit typed through `KEYEVENTF_UNICODE`.
+  pub fn unicode(unit: u16, state: KeyState) -> Self {
+    let mut flags = KeyboardInputFlags::Unicode;
+    flags.set(KeyboardInputFlags::KeyUp, matches!(state, KeyState::Up));
+    Self::Keyboard(KeyboardInput { vkey: 0, scancode: unit, flags })
+  }
+
+  /// Moves the cursor by a number of mickeys. Pointer acceleration still applies.
+  pub fn mouse_move(dx: i32, dy: i32) -> Self {
+    Self::Mouse(MouseInput { dx, dy, data: 0, flags: MouseInputFlags::Move })
+  }
+
+  /// Moves the cursor to `position`, given in coordinates of the virtual desktop spanning `desktop`.
+  pub fn mouse_move_absolute(position: PhysicalPosition<i32>, desktop: Rect) -> Self {
+    let (dx, dy) = normalize_absolute(position, desktop);
+    Self::Mouse(MouseInput {
+      dx,
+      dy,
+      data: 0,
+      flags: MouseInputFlags::Move | MouseInputFlags::Absolute | MouseInputFlags::VirtualDesk,
+    })
+  }
+
+  /// Positive deltas scroll away from the user. One notch is `WHEEL_DELTA`.
+  pub fn wheel(delta: i32) -> Self {
+    Self::Mouse(MouseInput { dx: 0, dy: 0, data: delta, flags: MouseInputFlags::Wheel })
+  }
+
+  /// Positive deltas scroll to the right. One notch is `WHEEL_DELTA`.
+  pub fn horizontal_wheel(delta: i32) -> Self {
+    Self::Mouse(MouseInput { dx: 0, dy: 0, data: delta, flags: MouseInputFlags::HorizontalWheel })
+  }
+
+  pub fn button(button: MouseButton, state: ButtonState) -> Option<Self> {
+    let down = matches!(state, ButtonState::Down);
+    #[allow(unreachable_patterns)]
+    let (flags, data) = match (button, down) {
+      (MouseButton::Left, true) => (MouseInputFlags::LeftDown, 0),
+      (MouseButton::Left, false) => (MouseInputFlags::LeftUp, 0),
+      (MouseButton::Right, true) => (MouseInputFlags::RightDown, 0),
+      (MouseButton::Right, false) => (MouseInputFlags::RightUp, 0),
+      (MouseButton::Middle, true) => (MouseInputFlags::MiddleDown, 0),
+      (MouseButton::Middle, false) => (MouseInputFlags::MiddleUp, 0),
+      (MouseButton::Back, true) => (MouseInputFlags::XDown, WindowsAndMessaging::XBUTTON1),
+      (MouseButton::Back, false) => (MouseInputFlags::XUp, WindowsAndMessaging::XBUTTON1),
+      (MouseButton::Forward, true) => (MouseInputFlags::XDown, WindowsAndMessaging::XBUTTON2),
+      (MouseButton::Forward, false) => (MouseInputFlags::XUp, WindowsAndMessaging::XBUTTON2),
+      _ => return None,
+    };
+    Some(Self::Mouse(MouseInput { dx: 0, dy: 0, data: data as i32, flags }))
+  }
+
+  pub fn to_raw(&self) -> INPUT {
+    match self {
+      Self::Keyboard(input) => INPUT {
+        r#type: KeyboardAndMouse::INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+          ki: KEYBDINPUT {
+            wVk: input.vkey,
+            wScan: input.scancode,
+            dwFlags: input.flags.to_raw(),
+            time: 0,
+            dwExtraInfo: 0,
+          },
+        },
+      },
+      Self::Mouse(input) => INPUT {
+        r#type: KeyboardAndMouse::INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+          mi: MOUSEINPUT {
+            dx: input.dx,
+            dy: input.dy,
+            mouseData: input.data as u32,
+            dwFlags: input.flags.to_raw(),
+            time: 0,
+            dwExtraInfo: 0,
+          },
+        },
+      },
+    }
+  }
+}
+
+/// Press and release of every UTF-16 code unit of `text`. Surrogate pairs are sent as two units, which
+/// is what applications receiving `WM_CHAR` expect.
+pub fn text(text: &str) -> Vec<Input> {
+  text
+    .encode_utf16()
+    .flat_map(|unit| [Input::unicode(unit, KeyState::Down), Input::unicode(unit, KeyState::Up)])
+    .collect()
+}
+
+/// Press and release of a mouse button.
+pub fn click(button: MouseButton) -> Option<[Input; 2]> {
+  Some([Input::button(button, ButtonState::Down)?, Input::button(button, ButtonState::Up)?])
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::input::scancode::code_to_scancode_for,
+    keyboard_types::Code,
+  };
+
+  fn keyboard(input: Input) -> KeyboardInput {
+    match input {
+      Input::Keyboard(input) => input,
+      Input::Mouse(input) => panic!("expected a keyboard input, got {input:?}"),
+    }
+  }
+
+  #[test]
+  fn text_is_sent_as_utf16_units() {
+    let down = KeyboardInputFlags::Unicode;
+    let up = KeyboardInputFlags::Unicode | KeyboardInputFlags::KeyUp;
+    // One unit for `é`, and a surrogate pair for `😀`, each unit pressed and released in turn.
+    let expected = [(0x00e9, down), (0x00e9, up), (0xd83d, down), (0xd83d, up), (0xde00, down), (0xde00, up)];
+    let inputs: Vec<_> = text("é😀").into_iter().map(keyboard).collect();
+    let expected: Vec<_> =
+      expected.into_iter().map(|(scancode, flags)| KeyboardInput { vkey: 0, scancode, flags }).collect();
+    assert_eq!(inputs, expected);
+    assert!(text("").is_empty());
+  }
+
+  #[test]
+  fn scancodes_carry_the_extended_flag() {
+    let cases = [
+      (Code::KeyA, 0x1e, false),
+      (Code::ArrowLeft, 0x4b, true),
+      (Code::Numpad4, 0x4b, false),
+      (Code::ControlRight, 0x1d, true),
+      (Code::NumpadEnter, 0x1c, true),
+      (Code::Enter, 0x1c, false),
+    ];
+    for (code, scancode, extended) in cases {
+      let input = keyboard(Input::scancode(code_to_scancode_for(code, false).unwrap(), KeyState::Down));
+      assert_eq!(input.vkey, 0);
+      assert_eq!(input.scancode, scancode, "{code}");
+      assert_eq!(input.flags.contains(KeyboardInputFlags::ExtendedKey), extended, "{code}");
+      assert!(input.flags.contains(KeyboardInputFlags::Scancode));
+      assert!(!input.flags.contains(KeyboardInputFlags::Unicode));
+    }
+  }
+
+  #[test]
+  fn key_up_is_only_set_on_release() {
+    for state in [KeyState::Down, KeyState::Up] {
+      let released = matches!(state, KeyState::Up);
+      let key = keyboard(Input::scancode(0xe04b, state));
+      assert_eq!(key.flags.contains(KeyboardInputFlags::KeyUp), released);
+      assert!(key.flags.contains(KeyboardInputFlags::ExtendedKey));
+      let unit = keyboard(Input::unicode(0x41, state));
+      assert_eq!(unit.flags.contains(KeyboardInputFlags::KeyUp), released);
+    }
+  }
+
+  #[test]
+  fn raw_inputs_keep_the_fields() {
+    let raw = Input::scancode(0xe04b, KeyState::Up).to_raw();
+    assert_eq!(raw.r#type, KeyboardAndMouse::INPUT_KEYBOARD);
+    let ki = unsafe { raw.Anonymous.ki };
+    assert_eq!((ki.wVk, ki.wScan), (0, 0x4b));
+    assert_eq!(
+      ki.dwFlags,
+      KeyboardAndMouse::KEYEVENTF_SCANCODE
+        | KeyboardAndMouse::KEYEVENTF_EXTENDEDKEY
+        | KeyboardAndMouse::KEYEVENTF_KEYUP
+    );
+
+    let raw = Input::wheel(-120).to_raw();
+    assert_eq!(raw.r#type, KeyboardAndMouse::INPUT_MOUSE);
+    let mi = unsafe { raw.Anonymous.mi };
+    assert_eq!((mi.mouseData as i32, mi.dwFlags), (-120, KeyboardAndMouse::MOUSEEVENTF_WHEEL));
+  }
+
+  #[test]
+  fn absolute_moves_cover_the_whole_desktop() {
+    let desktop = Rect { left: -1920, top: 0, right: 1920, bottom: 1080 };
+    let normalize = |x, y| normalize_absolute(PhysicalPosition::new(x, y), desktop);
+    assert_eq!(normalize(-1920, 0), (0, 0));
+    assert_eq!(normalize(1919, 1079), (ABSOLUTE_MAX, ABSOLUTE_MAX));
+    assert_eq!(normalize(-5000, 5000), (0, ABSOLUTE_MAX));
+  }
+}
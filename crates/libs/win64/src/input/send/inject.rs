@@ -0,0 +1,98 @@
+use {
+  super::{
+    Input,
+    click,
+    text,
+  },
+  crate::{
+    Rect,
+    convert_error,
+    get_last_error,
+    input::keyboard::code_to_scancode,
+    reset_last_error,
+  },
+  dpi::PhysicalPosition,
+  keyboard_types::{
+    Code,
+    KeyState,
+  },
+  pointer_types::mouse::MouseButton,
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::{
+    Foundation::ERROR_INVALID_PARAMETER,
+    UI::{
+      Input::KeyboardAndMouse::{
+        INPUT,
+        SendInput,
+      },
+      WindowsAndMessaging::{
+        self,
+        GetSystemMetrics,
+      },
+    },
+  },
+};
+
+impl Input {
+  /// A key event identified by scancode, so it is independent of the active keyboard layout.
+  pub fn key(code: Code, state: KeyState) -> Option<Self> {
+    Some(Self::scancode(code_to_scancode(code)?, state))
+  }
+}
+
+/// Press and release of a single key.
+pub fn key_press(code: Code) -> Option<[Input; 2]> {
+  Some([Input::key(code, KeyState::Down)?, Input::key(code, KeyState::Up)?])
+}
+
+/// The bounding rectangle of all monitors.
+pub fn virtual_desktop() -> Rect {
+  let (x, y, width, height) = unsafe {
+    (
+      GetSystemMetrics(WindowsAndMessaging::SM_XVIRTUALSCREEN),
+      GetSystemMetrics(WindowsAndMessaging::SM_YVIRTUALSCREEN),
+      GetSystemMetrics(WindowsAndMessaging::SM_CXVIRTUALSCREEN),
+      GetSystemMetrics(WindowsAndMessaging::SM_CYVIRTUALSCREEN),
+    )
+  };
+  Rect { left: x, top: y, right: x + width, bottom: y + height }
+}
+
+/// Injects `inputs` into the input stream and returns how many of them were injected. Fewer than
+/// requested means the input was blocked, for example by UIPI.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput"]
+pub fn send(inputs: &[Input]) -> Result<u32> {
+  let raw: Vec<INPUT> = inputs.iter().map(Input::to_raw).collect();
+  reset_last_error();
+  let sent = unsafe { SendInput(raw.len() as u32, raw.as_ptr(), std::mem::size_of::<INPUT>() as i32) };
+  match sent {
+    0 if !raw.is_empty() => Err(get_last_error().unwrap_or(Error::empty())),
+    _ => Ok(sent),
+  }
+}
+
+pub fn send_key_press(code: Code) -> Result<u32> {
+  match key_press(code) {
+    Some(inputs) => send(&inputs),
+    None => Err(convert_error(ERROR_INVALID_PARAMETER)),
+  }
+}
+
+pub fn send_text(value: &str) -> Result<u32> {
+  send(&text(value))
+}
+
+pub fn send_mouse_move_to(position: PhysicalPosition<i32>) -> Result<u32> {
+  send(&[Input::mouse_move_absolute(position, virtual_desktop())])
+}
+
+pub fn send_click(button: MouseButton) -> Result<u32> {
+  match click(button) {
+    Some(inputs) => send(&inputs),
+    None => Err(convert_error(ERROR_INVALID_PARAMETER)),
+  }
+}
@@ -0,0 +1,562 @@
+use {
+  keyboard_types::{
+    Code,
+    Modifiers,
+  },
+  std::{
+    fmt::{
+      self,
+      Display,
+    },
+    str::FromStr,
+  },
+};
+
+/// A combination of modifiers and a physical key, such as `Ctrl+Shift+K`.
+///
+/// Only [`Modifiers::CONTROL`], [`Modifiers::ALT`], [`Modifiers::SHIFT`] and [`Modifiers::META`] take part
+/// in shortcuts. The key is stored as a [`Code`] so that a shortcut stays on the same physical key across
+/// layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shortcut {
+  pub modifiers: Modifiers,
+  pub code: Code,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParseShortcutError {
+  Empty,
+  MissingKey,
+  MultipleKeys,
+  UnknownKey(String),
+}
+
+impl Display for ParseShortcutError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Empty => write!(f, "shortcut is empty"),
+      Self::MissingKey => write!(f, "shortcut has no key"),
+      Self::MultipleKeys => write!(f, "shortcut has more than one key"),
+      Self::UnknownKey(key) => write!(f, "unrecognized key `{key}`"),
+    }
+  }
+}
+
+impl std::error::Error for ParseShortcutError {}
+
+impl Shortcut {
+  pub const MODIFIERS: Modifiers =
+    Modifiers::CONTROL.union(Modifiers::ALT).union(Modifiers::SHIFT).union(Modifiers::META);
+  pub const SEPARATOR: char = '+';
+
+  /// Modifiers outside of [`Shortcut::MODIFIERS`] are discarded.
+  pub const fn new(modifiers: Modifiers, code: Code) -> Self {
+    Self { modifiers: modifiers.intersection(Self::MODIFIERS), code }
+  }
+}
+
+// Modifiers are always written in this order, regardless of how they were parsed.
+pub(crate) const MODIFIER_NAMES: [(Modifiers, &str); 4] = [
+  (Modifiers::CONTROL, "Ctrl"),
+  (Modifiers::ALT, "Alt"),
+  (Modifiers::SHIFT, "Shift"),
+  (Modifiers::META, "Win"),
+];
+
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+  match name.to_ascii_lowercase().as_str() {
+    "ctrl" | "control" => Some(Modifiers::CONTROL),
+    "alt" | "menu" => Some(Modifiers::ALT),
+    "shift" => Some(Modifiers::SHIFT),
+    "win" | "windows" | "meta" | "super" | "cmd" => Some(Modifiers::META),
+    _ => None,
+  }
+}
+
+/// Every [`Code`] with a name, to match names regardless of case.
+#[allow(deprecated)]
+const CODES: &[Code] = &[
+  Code::Backquote,
+  Code::Backslash,
+  Code::BracketLeft,
+  Code::BracketRight,
+  Code::Comma,
+  Code::Digit0,
+  Code::Digit1,
+  Code::Digit2,
+  Code::Digit3,
+  Code::Digit4,
+  Code::Digit5,
+  Code::Digit6,
+  Code::Digit7,
+  Code::Digit8,
+  Code::Digit9,
+  Code::Equal,
+  Code::IntlBackslash,
+  Code::IntlRo,
+  Code::IntlYen,
+  Code::KeyA,
+  Code::KeyB,
+  Code::KeyC,
+  Code::KeyD,
+  Code::KeyE,
+  Code::KeyF,
+  Code::KeyG,
+  Code::KeyH,
+  Code::KeyI,
+  Code::KeyJ,
+  Code::KeyK,
+  Code::KeyL,
+  Code::KeyM,
+  Code::KeyN,
+  Code::KeyO,
+  Code::KeyP,
+  Code::KeyQ,
+  Code::KeyR,
+  Code::KeyS,
+  Code::KeyT,
+  Code::KeyU,
+  Code::KeyV,
+  Code::KeyW,
+  Code::KeyX,
+  Code::KeyY,
+  Code::KeyZ,
+  Code::Minus,
+  Code::Period,
+  Code::Quote,
+  Code::Semicolon,
+  Code::Slash,
+  Code::AltLeft,
+  Code::AltRight,
+  Code::Backspace,
+  Code::CapsLock,
+  Code::ContextMenu,
+  Code::ControlLeft,
+  Code::ControlRight,
+  Code::Enter,
+  Code::MetaLeft,
+  Code::MetaRight,
+  Code::ShiftLeft,
+  Code::ShiftRight,
+  Code::Space,
+  Code::Tab,
+  Code::Convert,
+  Code::KanaMode,
+  Code::Lang1,
+  Code::Lang2,
+  Code::Lang3,
+  Code::Lang4,
+  Code::Lang5,
+  Code::NonConvert,
+  Code::Delete,
+  Code::End,
+  Code::Help,
+  Code::Home,
+  Code::Insert,
+  Code::PageDown,
+  Code::PageUp,
+  Code::ArrowDown,
+  Code::ArrowLeft,
+  Code::ArrowRight,
+  Code::ArrowUp,
+  Code::NumLock,
+  Code::Numpad0,
+  Code::Numpad1,
+  Code::Numpad2,
+  Code::Numpad3,
+  Code::Numpad4,
+  Code::Numpad5,
+  Code::Numpad6,
+  Code::Numpad7,
+  Code::Numpad8,
+  Code::Numpad9,
+  Code::NumpadAdd,
+  Code::NumpadBackspace,
+  Code::NumpadClear,
+  Code::NumpadClearEntry,
+  Code::NumpadComma,
+  Code::NumpadDecimal,
+  Code::NumpadDivide,
+  Code::NumpadEnter,
+  Code::NumpadEqual,
+  Code::NumpadHash,
+  Code::NumpadMemoryAdd,
+  Code::NumpadMemoryClear,
+  Code::NumpadMemoryRecall,
+  Code::NumpadMemoryStore,
+  Code::NumpadMemorySubtract,
+  Code::NumpadMultiply,
+  Code::NumpadParenLeft,
+  Code::NumpadParenRight,
+  Code::NumpadStar,
+  Code::NumpadSubtract,
+  Code::Escape,
+  Code::Fn,
+  Code::FnLock,
+  Code::PrintScreen,
+  Code::ScrollLock,
+  Code::Pause,
+  Code::BrowserBack,
+  Code::BrowserFavorites,
+  Code::BrowserForward,
+  Code::BrowserHome,
+  Code::BrowserRefresh,
+  Code::BrowserSearch,
+  Code::BrowserStop,
+  Code::Eject,
+  Code::LaunchApp1,
+  Code::LaunchApp2,
+  Code::LaunchMail,
+  Code::MediaPlayPause,
+  Code::MediaSelect,
+  Code::MediaStop,
+  Code::MediaTrackNext,
+  Code::MediaTrackPrevious,
+  Code::Power,
+  Code::Sleep,
+  Code::AudioVolumeDown,
+  Code::AudioVolumeMute,
+  Code::AudioVolumeUp,
+  Code::WakeUp,
+  Code::Hyper,
+  Code::Super,
+  Code::Turbo,
+  Code::Abort,
+  Code::Resume,
+  Code::Suspend,
+  Code::Again,
+  Code::Copy,
+  Code::Cut,
+  Code::Find,
+  Code::Open,
+  Code::Paste,
+  Code::Props,
+  Code::Select,
+  Code::Undo,
+  Code::Hiragana,
+  Code::Katakana,
+  Code::F1,
+  Code::F2,
+  Code::F3,
+  Code::F4,
+  Code::F5,
+  Code::F6,
+  Code::F7,
+  Code::F8,
+  Code::F9,
+  Code::F10,
+  Code::F11,
+  Code::F12,
+  Code::F13,
+  Code::F14,
+  Code::F15,
+  Code::F16,
+  Code::F17,
+  Code::F18,
+  Code::F19,
+  Code::F20,
+  Code::F21,
+  Code::F22,
+  Code::F23,
+  Code::F24,
+  Code::F25,
+  Code::F26,
+  Code::F27,
+  Code::F28,
+  Code::F29,
+  Code::F30,
+  Code::F31,
+  Code::F32,
+  Code::F33,
+  Code::F34,
+  Code::F35,
+  Code::BrightnessDown,
+  Code::BrightnessUp,
+  Code::DisplayToggleIntExt,
+  Code::KeyboardLayoutSelect,
+  Code::LaunchAssistant,
+  Code::LaunchControlPanel,
+  Code::LaunchScreenSaver,
+  Code::MailForward,
+  Code::MailReply,
+  Code::MailSend,
+  Code::MediaFastForward,
+  Code::MediaPause,
+  Code::MediaPlay,
+  Code::MediaRecord,
+  Code::MediaRewind,
+  Code::MicrophoneMuteToggle,
+  Code::PrivacyScreenToggle,
+  Code::KeyboardBacklightToggle,
+  Code::SelectTask,
+  Code::ShowAllWindows,
+  Code::ZoomToggle,
+];
+
+/// The other names [`Code::from_str`] accepts.
+const CODE_ALIASES: [(&str, Code); 6] = [
+  ("OSLeft", Code::MetaLeft),
+  ("OSRight", Code::MetaRight),
+  ("LaunchMediaPlayer", Code::MediaSelect),
+  ("VolumeDown", Code::AudioVolumeDown),
+  ("VolumeMute", Code::AudioVolumeMute),
+  ("VolumeUp", Code::AudioVolumeUp),
+];
+
+/// The name used for `code` in the canonical string form of a shortcut.
+pub fn key_name(code: Code) -> String {
+  let name = match code {
+    Code::Minus => "-",
+    Code::Equal => "=",
+    Code::BracketLeft => "[",
+    Code::BracketRight => "]",
+    Code::Backslash => "\\",
+    Code::Semicolon => ";",
+    Code::Quote => "'",
+    Code::Backquote => "`",
+    Code::Comma => ",",
+    Code::Period => ".",
+    Code::Slash => "/",
+    Code::Escape => "Esc",
+    Code::Delete => "Del",
+    Code::Insert => "Ins",
+    Code::ArrowUp => "Up",
+    Code::ArrowDown => "Down",
+    Code::ArrowLeft => "Left",
+    Code::ArrowRight => "Right",
+    code => {
+      let name = code.to_string();
+      return match name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")) {
+        Some(stripped) if stripped.len() == 1 => stripped.to_string(),
+        _ => name,
+      };
+    },
+  };
+  name.to_string()
+}
+
+/// The inverse of [`key_name`]. Also accepts the [`Code`] names themselves and a few common aliases,
+/// ignoring case.
+pub fn parse_key_name(name: &str) -> Option<Code> {
+  if let Ok(code) = Code::from_str(name)
+    && code != Code::Unidentified
+  {
+    return Some(code);
+  }
+
+  let lower = name.to_ascii_lowercase();
+  let code = match lower.as_str() {
+    "-" => Code::Minus,
+    "=" => Code::Equal,
+    "[" => Code::BracketLeft,
+    "]" => Code::BracketRight,
+    "\\" => Code::Backslash,
+    ";" => Code::Semicolon,
+    "'" => Code::Quote,
+    "`" => Code::Backquote,
+    "," => Code::Comma,
+    "." => Code::Period,
+    "/" => Code::Slash,
+    "esc" | "escape" => Code::Escape,
+    "del" | "delete" => Code::Delete,
+    "ins" | "insert" => Code::Insert,
+    "up" => Code::ArrowUp,
+    "down" => Code::ArrowDown,
+    "left" => Code::ArrowLeft,
+    "right" => Code::ArrowRight,
+    "enter" | "return" => Code::Enter,
+    "space" => Code::Space,
+    "tab" => Code::Tab,
+    "backspace" => Code::Backspace,
+    "home" => Code::Home,
+    "end" => Code::End,
+    "pgup" | "pageup" => Code::PageUp,
+    "pgdn" | "pagedown" => Code::PageDown,
+    _ => {
+      let mut chars = lower.chars();
+      return match (chars.next(), chars.next()) {
+        (Some(c @ 'a'..='z'), None) => Code::from_str(&format!("Key{}", c.to_ascii_uppercase())).ok(),
+        (Some(c @ '0'..='9'), None) => Code::from_str(&format!("Digit{c}")).ok(),
+        // The remaining `Code` names in any case, e.g. `f5`, `numpadadd` or `capslock`.
+        _ => CODES.iter().copied().find(|code| code.to_string().eq_ignore_ascii_case(name)).or_else(|| {
+          CODE_ALIASES.iter().find(|(alias, _)| alias.eq_ignore_ascii_case(name)).map(|(_, code)| *code)
+        }),
+      };
+    },
+  };
+  Some(code)
+}
+
+impl FromStr for Shortcut {
+  type Err = ParseShortcutError;
+
+  fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    let s = s.trim();
+    if s.is_empty() {
+      return Err(ParseShortcutError::Empty);
+    }
+
+    let mut modifiers = Modifiers::empty();
+    let mut code = None;
+    let mut parts = s.split(Self::SEPARATOR).map(str::trim).peekable();
+    while let Some(part) = parts.next() {
+      // A modifier name that ends a shortcut without a key is the key if there is one by that name, e.g.
+      // `Ctrl+Super` for `Code::Super`.
+      let is_key = code.is_none() && parts.peek().is_none() && parse_key_name(part).is_some();
+      if let Some(modifier) = parse_modifier(part)
+        && !is_key
+      {
+        modifiers |= modifier;
+        continue;
+      }
+
+      let key = parse_key_name(part).ok_or_else(|| ParseShortcutError::UnknownKey(part.to_string()))?;
+      if code.replace(key).is_some() {
+        return Err(ParseShortcutError::MultipleKeys);
+      }
+    }
+
+    match code {
+      Some(code) => Ok(Self::new(modifiers, code)),
+      None => Err(ParseShortcutError::MissingKey),
+    }
+  }
+}
+
+impl Display for Shortcut {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (modifier, name) in MODIFIER_NAMES {
+      if self.modifiers.contains(modifier) {
+        write!(f, "{name}{}", Self::SEPARATOR)?;
+      }
+    }
+    write!(f, "{}", key_name(self.code))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::input::scancode::scancode_to_code,
+  };
+
+  fn shortcut(s: &str) -> Shortcut {
+    s.parse().unwrap_or_else(|error| panic!("`{s}`: {error}"))
+  }
+
+  #[test]
+  fn the_name_table_covers_every_scancode() {
+    for code in (0..=0xffff).map(scancode_to_code).filter(|code| *code != Code::Unidentified) {
+      assert!(CODES.contains(&code), "{code}");
+    }
+  }
+
+  #[test]
+  fn every_key_round_trips() {
+    for &code in CODES {
+      for bits in 0..16 {
+        let modifiers = MODIFIER_NAMES
+          .iter()
+          .enumerate()
+          .filter(|(i, _)| bits & (1 << i) != 0)
+          .fold(Modifiers::empty(), |modifiers, (_, (modifier, _))| modifiers | *modifier);
+        let original = Shortcut::new(modifiers, code);
+        let formatted = original.to_string();
+        let parsed = shortcut(&formatted);
+        assert_eq!(parsed, original, "`{formatted}`");
+        assert_eq!(parsed.to_string(), formatted);
+      }
+    }
+  }
+
+  #[test]
+  fn modifiers_are_written_in_a_fixed_order() {
+    assert_eq!(shortcut("Win+Shift+Alt+Ctrl+K").to_string(), "Ctrl+Alt+Shift+Win+K");
+    assert_eq!(shortcut("Shift+Ctrl+Shift+1").to_string(), "Ctrl+Shift+1");
+  }
+
+  #[test]
+  fn case_and_whitespace_are_ignored() {
+    let expected = Shortcut::new(Modifiers::CONTROL | Modifiers::SHIFT, Code::KeyK);
+    for s in ["Ctrl+Shift+K", "ctrl+shift+k", "CTRL+SHIFT+K", " Ctrl + Shift + K ", "control+Shift+KeyK"] {
+      assert_eq!(shortcut(s), expected, "`{s}`");
+    }
+    assert_eq!(shortcut("alt+f5"), Shortcut::new(Modifiers::ALT, Code::F5));
+    assert_eq!(shortcut("NUMPAD0"), Shortcut::new(Modifiers::empty(), Code::Numpad0));
+  }
+
+  #[test]
+  fn names_of_several_words_ignore_case() {
+    let cases = [
+      ("numpadadd", Code::NumpadAdd),
+      ("arrowup", Code::ArrowUp),
+      ("capslock", Code::CapsLock),
+      ("pageup", Code::PageUp),
+      ("PRINTSCREEN", Code::PrintScreen),
+      ("mediaPlayPause", Code::MediaPlayPause),
+      ("numpadclearentry", Code::NumpadClearEntry),
+      ("osleft", Code::MetaLeft),
+      ("volumeup", Code::AudioVolumeUp),
+    ];
+    for (name, code) in cases {
+      assert_eq!(parse_key_name(name), Some(code), "`{name}`");
+      assert_eq!(
+        shortcut(&format!("ctrl+shift+{name}")),
+        Shortcut::new(Modifiers::CONTROL | Modifiers::SHIFT, code)
+      );
+    }
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn a_modifier_name_at_the_end_is_the_key() {
+    assert_eq!(shortcut("Ctrl+Super"), Shortcut::new(Modifiers::CONTROL, Code::Super));
+    assert_eq!(shortcut("super"), Shortcut::new(Modifiers::empty(), Code::Super));
+    assert_eq!(shortcut("Super+Super"), Shortcut::new(Modifiers::META, Code::Super));
+    assert_eq!(shortcut("Super+K"), Shortcut::new(Modifiers::META, Code::KeyK));
+    assert_eq!(shortcut("K+Super"), Shortcut::new(Modifiers::META, Code::KeyK));
+  }
+
+  #[test]
+  fn aliases_parse_to_the_same_shortcut() {
+    let aliases: &[(&[&str], Shortcut)] = &[
+      (&["Ctrl+A", "Control+A"], Shortcut::new(Modifiers::CONTROL, Code::KeyA)),
+      (&["Alt+A", "Menu+A"], Shortcut::new(Modifiers::ALT, Code::KeyA)),
+      (&["Win+A", "Windows+A", "Meta+A", "Super+A", "Cmd+A"], Shortcut::new(Modifiers::META, Code::KeyA)),
+      (&["Esc", "Escape"], Shortcut::new(Modifiers::empty(), Code::Escape)),
+      (&["Del", "Delete"], Shortcut::new(Modifiers::empty(), Code::Delete)),
+      (&["Ins", "Insert"], Shortcut::new(Modifiers::empty(), Code::Insert)),
+      (&["Enter", "Return"], Shortcut::new(Modifiers::empty(), Code::Enter)),
+      (&["PgUp", "PageUp"], Shortcut::new(Modifiers::empty(), Code::PageUp)),
+      (&["PgDn", "PageDown"], Shortcut::new(Modifiers::empty(), Code::PageDown)),
+      (&["Up", "ArrowUp"], Shortcut::new(Modifiers::empty(), Code::ArrowUp)),
+      (&["-", "Minus"], Shortcut::new(Modifiers::empty(), Code::Minus)),
+      (&["7", "Digit7"], Shortcut::new(Modifiers::empty(), Code::Digit7)),
+    ];
+    for (names, expected) in aliases {
+      for name in *names {
+        assert_eq!(shortcut(name), *expected, "`{name}`");
+      }
+    }
+  }
+
+  #[test]
+  fn invalid_shortcuts_are_rejected() {
+    let cases = [
+      ("", ParseShortcutError::Empty),
+      ("   ", ParseShortcutError::Empty),
+      ("Ctrl", ParseShortcutError::MissingKey),
+      ("Ctrl+Shift", ParseShortcutError::MissingKey),
+      ("Ctrl+K+L", ParseShortcutError::MultipleKeys),
+      ("A+A", ParseShortcutError::MultipleKeys),
+      ("Ctrl+Foo", ParseShortcutError::UnknownKey("Foo".to_string())),
+      ("Ctrl+Unidentified", ParseShortcutError::UnknownKey("Unidentified".to_string())),
+      ("Ctrl+", ParseShortcutError::UnknownKey(String::new())),
+      ("Ctrl+KK", ParseShortcutError::UnknownKey("KK".to_string())),
+    ];
+    for (s, expected) in cases {
+      assert_eq!(s.parse::<Shortcut>(), Err(expected), "`{s}`");
+    }
+  }
+}
@@ -1,4 +1,3 @@
-#![cfg(target_os = "windows")]
 //!
 //! Tweaks that are made should be limited to things such as:
 //!
@@ -10,24 +9,40 @@
 //! and docs with minimal resistance while also benefitting from strong type-checking and clearer APIs.
 //!
 
+#[cfg(target_os = "windows")]
 pub mod core;
+#[cfg(target_os = "windows")]
 pub use core::*;
 
-#[cfg(feature = "safe")]
+#[cfg(all(target_os = "windows", feature = "safe"))]
 pub mod safe;
 
+#[cfg(target_os = "windows")]
 pub mod user;
 
+// These work on plain data without calling into Win32, so unlike the rest of the crate they build and run
+// their tests on every target. The parts of `input` that do call into Win32 are gated inside it.
 pub mod input;
 
+pub mod ico;
+
+pub mod pixel;
+
+pub mod placement;
+
+pub mod rect;
 pub use {
   dpi,
   keyboard_types,
+  rect::*,
 };
 
+#[cfg(target_os = "windows")]
 pub mod error;
+#[cfg(target_os = "windows")]
 pub use error::*;
 
+#[cfg(target_os = "windows")]
 pub mod sys {
   pub use windows_sys::Win32::{
     Foundation::*,
@@ -41,6 +56,7 @@ pub use rwh_05 as raw_window_handle;
 #[cfg(all(feature = "rwh_06", not(any(feature = "rwh_05"))))]
 pub use rwh_06 as raw_window_handle;
 
+#[cfg(target_os = "windows")]
 pub mod prelude {
   pub use crate::{
     dpi::*,
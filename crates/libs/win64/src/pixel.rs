@@ -0,0 +1,60 @@
+//!
+//! Converting pixel buffers into the layouts GDI expects.
+//!
+//! GDI stores 32-bit pixels as BGRA, and layered windows and alpha blending additionally expect each
+//! color channel to be premultiplied by alpha.
+//!
+
+use rgb::RGBA8;
+
+/// `pixels` as BGRA bytes, leaving the colors as they are.
+pub fn to_bgra(pixels: &[RGBA8]) -> Vec<u8> {
+  pixels.iter().flat_map(|pixel| [pixel.b, pixel.g, pixel.r, pixel.a]).collect()
+}
+
+/// `pixels` as BGRA bytes with every color channel scaled by alpha, as `UpdateLayeredWindow` and
+/// `AlphaBlend` expect. Fully transparent pixels come out all zero.
+pub fn to_premultiplied_bgra(pixels: &[RGBA8]) -> Vec<u8> {
+  pixels
+    .iter()
+    .flat_map(|pixel| {
+      let RGBA8 { r, g, b, a } = *pixel;
+      [premultiply(b, a), premultiply(g, a), premultiply(r, a), a]
+    })
+    .collect()
+}
+
+/// Scales `channel` by `alpha / 255`, rounding to the nearest value.
+pub const fn premultiply(channel: u8, alpha: u8) -> u8 {
+  let product = channel as u32 * alpha as u32 + 128;
+  // Divides by 255 without the division, exactly for every product of two bytes.
+  ((product + (product >> 8)) >> 8) as u8
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn premultiply_rounds_to_nearest_for_every_pair() {
+    for channel in 0..=255u8 {
+      for alpha in 0..=255u8 {
+        let expected = (channel as u32 * alpha as u32 + 127) / 255;
+        assert_eq!(premultiply(channel, alpha) as u32, expected, "{channel} * {alpha}");
+      }
+    }
+  }
+
+  #[test]
+  fn bgra_byte_order() {
+    let pixels = [RGBA8::new(1, 2, 3, 4), RGBA8::new(5, 6, 7, 8)];
+    assert_eq!(to_bgra(&pixels), [3, 2, 1, 4, 7, 6, 5, 8]);
+    assert_eq!(to_bgra(&[]), []);
+  }
+
+  #[test]
+  fn premultiplied_bgra_keeps_alpha_and_order() {
+    let pixels = [RGBA8::new(255, 128, 0, 255), RGBA8::new(255, 128, 64, 128), RGBA8::new(255, 255, 255, 0)];
+    assert_eq!(to_premultiplied_bgra(&pixels), [0, 128, 255, 255, 32, 64, 128, 128, 0, 0, 0, 0]);
+  }
+}
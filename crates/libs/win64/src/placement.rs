@@ -0,0 +1,248 @@
+//!
+//! Saving and restoring where a window is.
+//!
+//! A [`WindowPlacement`] saved in one run may not fit the monitors of the next, so it can be moved back
+//! onto them before it is applied. Applying a placement is up to `Window::set_placement`.
+//!
+
+use {
+  crate::Rect,
+  dpi::PhysicalPosition,
+  windows_sys::Win32::{
+    Foundation::POINT,
+    UI::WindowsAndMessaging::{
+      self,
+      WINDOWPLACEMENT,
+      WPF_RESTORETOMAXIMIZED,
+      WPF_SETMINPOSITION,
+    },
+  },
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlacementState {
+  #[default]
+  Normal,
+  Minimized,
+  Maximized,
+}
+
+/// The size, position and show state of a top-level window, as saved by `GetWindowPlacement`.
+///
+/// Positions are in workspace coordinates, which are offset from screen coordinates by the work area of
+/// the primary monitor. That keeps a saved placement from ending up under a taskbar that has moved to
+/// the top or left edge since it was saved.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-windowplacement"]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowPlacement {
+  pub state: PlacementState,
+  /// The window's rectangle while it is neither minimized nor maximized.
+  pub normal_rect: Rect,
+  /// Where the window goes when it is minimized. `(-1, -1)` lets Windows pick.
+  pub min_position: PhysicalPosition<i32>,
+  /// Where the window goes when it is maximized. `(-1, -1)` lets Windows pick.
+  pub max_position: PhysicalPosition<i32>,
+  /// Whether restoring a minimized window maximizes it, because it was maximized before it was
+  /// minimized.
+  pub restore_to_maximized: bool,
+}
+
+/// How much of a window's top edge has to be on a monitor for the window to count as reachable, in
+/// pixels. Any less and there is nothing left to grab it by.
+const MIN_VISIBLE: i64 = 48;
+
+const DEFAULT_POSITION: PhysicalPosition<i32> = PhysicalPosition::new(-1, -1);
+
+impl WindowPlacement {
+  pub fn from_raw(placement: &WINDOWPLACEMENT) -> Self {
+    let position = |point: POINT| PhysicalPosition::new(point.x, point.y);
+    Self {
+      state: match placement.showCmd as i32 {
+        WindowsAndMessaging::SW_SHOWMINIMIZED | WindowsAndMessaging::SW_MINIMIZE => PlacementState::Minimized,
+        WindowsAndMessaging::SW_SHOWMAXIMIZED => PlacementState::Maximized,
+        _ => PlacementState::Normal,
+      },
+      normal_rect: placement.rcNormalPosition.into(),
+      min_position: position(placement.ptMinPosition),
+      max_position: position(placement.ptMaxPosition),
+      restore_to_maximized: (placement.flags & WPF_RESTORETOMAXIMIZED) != 0,
+    }
+  }
+
+  pub fn to_raw(&self) -> WINDOWPLACEMENT {
+    let point = |position: PhysicalPosition<i32>| POINT { x: position.x, y: position.y };
+    let mut flags = 0;
+    if self.restore_to_maximized {
+      flags |= WPF_RESTORETOMAXIMIZED;
+    }
+    if self.min_position != DEFAULT_POSITION {
+      flags |= WPF_SETMINPOSITION;
+    }
+    WINDOWPLACEMENT {
+      length: size_of::<WINDOWPLACEMENT>() as u32,
+      flags,
+      showCmd: match self.state {
+        PlacementState::Normal => WindowsAndMessaging::SW_SHOWNORMAL,
+        PlacementState::Minimized => WindowsAndMessaging::SW_SHOWMINIMIZED,
+        PlacementState::Maximized => WindowsAndMessaging::SW_SHOWMAXIMIZED,
+      } as u32,
+      ptMinPosition: point(self.min_position),
+      ptMaxPosition: point(self.max_position),
+      rcNormalPosition: self.normal_rect.to_raw(),
+    }
+  }
+
+  /// Whether enough of the normal rectangle's top edge is inside `work_areas` to grab the window by its
+  /// title bar. The edge may be split across several work areas, as long as they add up.
+  pub fn is_reachable(&self, work_areas: &[Rect]) -> bool {
+    let rect = self.normal_rect;
+    // Widened, since rectangles made up from saved settings can span more than an `i32` can hold.
+    let width = rect.right as i64 - rect.left as i64;
+    let visible_width: i64 = work_areas
+      .iter()
+      .filter(|area| rect.top >= area.top && rect.top < area.bottom)
+      .map(|area| (rect.right.min(area.right) as i64 - rect.left.max(area.left) as i64).max(0))
+      .sum();
+    visible_width >= width.clamp(1, MIN_VISIBLE)
+  }
+
+  /// Moves the normal rectangle onto the nearest of `work_areas` if it isn't reachable, shrinking it to
+  /// fit if necessary. The minimized and maximized positions are reset so Windows picks new ones.
+  ///
+  /// This doesn't touch any monitors itself, so `work_areas` can be made up. `Window::restore_placement`
+  /// passes the work areas of the connected monitors.
+  pub fn fit_to(&self, work_areas: &[Rect]) -> Self {
+    if work_areas.is_empty() || self.is_reachable(work_areas) {
+      return *self;
+    }
+
+    let rect = self.normal_rect;
+    let center =
+      |rect: &Rect| ((rect.left as i64 + rect.right as i64) / 2, (rect.top as i64 + rect.bottom as i64) / 2);
+    let (x, y) = center(&rect);
+    let area = work_areas
+      .iter()
+      .min_by_key(|area| {
+        let (area_x, area_y) = center(area);
+        let (dx, dy) = (area_x - x, area_y - y);
+        dx.saturating_mul(dx).saturating_add(dy.saturating_mul(dy))
+      })
+      .unwrap();
+
+    let width = rect.right.saturating_sub(rect.left).clamp(0, area.right.saturating_sub(area.left).max(0));
+    let height = rect.bottom.saturating_sub(rect.top).clamp(0, area.bottom.saturating_sub(area.top).max(0));
+    // Not `clamp`, which panics on the inverted range of an empty work area.
+    let left = rect.left.min(area.right.saturating_sub(width)).max(area.left);
+    let top = rect.top.min(area.bottom.saturating_sub(height)).max(area.top);
+
+    Self {
+      normal_rect: Rect { left, top, right: left.saturating_add(width), bottom: top.saturating_add(height) },
+      min_position: DEFAULT_POSITION,
+      max_position: DEFAULT_POSITION,
+      ..*self
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const LEFT: Rect = Rect { left: 0, top: 0, right: 1920, bottom: 1040 };
+  const RIGHT: Rect = Rect { left: 1920, top: 0, right: 4480, bottom: 1400 };
+
+  fn saved(left: i32, top: i32, right: i32, bottom: i32) -> WindowPlacement {
+    WindowPlacement {
+      normal_rect: Rect { left, top, right, bottom },
+      min_position: PhysicalPosition::new(10, 10),
+      max_position: PhysicalPosition::new(20, 20),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn reachable_placements_are_left_alone() {
+    let placement = saved(100, 100, 900, 700);
+    assert!(placement.is_reachable(&[LEFT, RIGHT]));
+    assert_eq!(placement.fit_to(&[LEFT, RIGHT]), placement);
+    assert_eq!(placement.fit_to(&[]), placement);
+  }
+
+  #[test]
+  fn off_screen_placements_move_to_the_nearest_work_area() {
+    let placement = saved(6000, 300, 6800, 900);
+    assert!(!placement.is_reachable(&[LEFT, RIGHT]));
+
+    let fitted = placement.fit_to(&[LEFT, RIGHT]);
+    assert_eq!(fitted.normal_rect, Rect { left: 3680, top: 300, right: 4480, bottom: 900 });
+    assert_eq!(fitted.min_position, DEFAULT_POSITION);
+    assert_eq!(fitted.max_position, DEFAULT_POSITION);
+    assert_eq!(fitted.state, placement.state);
+
+    let above = saved(100, -2000, 900, -1400).fit_to(&[LEFT, RIGHT]);
+    assert_eq!(above.normal_rect, Rect { left: 100, top: 0, right: 900, bottom: 600 });
+  }
+
+  #[test]
+  fn placements_across_work_areas() {
+    // Straddling both monitors, with enough of the title bar on the right one.
+    let straddling = saved(1800, 100, 2600, 700);
+    assert!(straddling.is_reachable(&[LEFT, RIGHT]));
+    assert_eq!(straddling.fit_to(&[LEFT, RIGHT]), straddling);
+
+    // Below the shorter left monitor, but still on the taller right one.
+    let low = saved(1800, 1200, 2600, 1300);
+    assert!(low.is_reachable(&[LEFT, RIGHT]));
+    assert!(!low.is_reachable(&[LEFT]));
+
+    // Too little of the title bar on either monitor alone, but enough on both together.
+    let split = saved(1890, 10, 1950, 1000);
+    assert!(split.is_reachable(&[LEFT, RIGHT]));
+    assert!(!split.is_reachable(&[LEFT]));
+    assert_eq!(split.fit_to(&[LEFT]).normal_rect, Rect { left: 1860, top: 10, right: 1920, bottom: 1000 });
+
+    // Overlapping both monitors, but with the title bar above them.
+    let above = saved(1800, -50, 2600, 700);
+    assert!(!above.is_reachable(&[LEFT, RIGHT]));
+    let fitted = above.fit_to(&[LEFT, RIGHT]);
+    assert_eq!(fitted.normal_rect, Rect { left: 1920, top: 0, right: 2720, bottom: 750 });
+  }
+
+  #[test]
+  fn oversized_placements_shrink_to_the_work_area() {
+    let fitted = saved(-3000, -2000, 9000, 5000).fit_to(&[LEFT]);
+    assert_eq!(fitted.normal_rect, LEFT);
+    assert!(fitted.is_reachable(&[LEFT]));
+
+    let fitted = saved(-100, -100, 3000, 500).fit_to(&[LEFT]);
+    assert_eq!(fitted.normal_rect, Rect { left: 0, top: 0, right: 1920, bottom: 600 });
+  }
+
+  #[test]
+  fn extreme_rectangles_do_not_overflow() {
+    let everything = saved(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+    assert!(!everything.is_reachable(&[LEFT, RIGHT]));
+    assert_eq!(everything.fit_to(&[RIGHT]).normal_rect, RIGHT);
+
+    let huge_area = Rect { left: i32::MIN, top: i32::MIN, right: i32::MAX, bottom: i32::MAX };
+    let fitted = saved(i32::MAX - 10, i32::MIN, i32::MAX, i32::MIN + 10).fit_to(&[LEFT, huge_area]);
+    assert!(fitted.is_reachable(&[huge_area]));
+
+    // Inverted rectangles, e.g. from a corrupt save, come out empty rather than panicking.
+    let inverted = saved(500, 500, 100, 100).fit_to(&[Rect { left: 10, top: 10, right: 0, bottom: 0 }]);
+    assert_eq!(inverted.normal_rect.right - inverted.normal_rect.left, 0);
+  }
+
+  #[test]
+  fn raw_round_trip() {
+    let mut placement = saved(100, 100, 900, 700);
+    for state in [PlacementState::Normal, PlacementState::Minimized, PlacementState::Maximized] {
+      placement.state = state;
+      placement.restore_to_maximized = state == PlacementState::Minimized;
+      assert_eq!(WindowPlacement::from_raw(&placement.to_raw()), placement);
+    }
+  }
+}
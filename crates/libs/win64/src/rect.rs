@@ -1,6 +1,10 @@
-use windows_sys::Win32::Foundation::RECT;
+use {
+  dpi::PhysicalPosition,
+  windows_sys::Win32::Foundation::RECT,
+};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect {
   pub left: i32,
   pub top: i32,
@@ -12,6 +16,11 @@ impl Rect {
   pub fn to_raw(&self) -> RECT {
     RECT { left: self.left, top: self.top, right: self.right, bottom: self.bottom }
   }
+
+  /// Whether `position` is inside, counting the left and top edges but not the right and bottom ones.
+  pub const fn contains(&self, position: PhysicalPosition<i32>) -> bool {
+    position.x >= self.left && position.x < self.right && position.y >= self.top && position.y < self.bottom
+  }
 }
 
 impl From<RECT> for Rect {
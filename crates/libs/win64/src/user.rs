@@ -18,17 +18,72 @@ pub mod class;
 pub use class::*;
 
 pub mod winmain;
-use windows_sys::Win32::UI::HiDpi::{
-  DPI_AWARENESS_CONTEXT,
-  DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
-  DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
-  DPI_AWARENESS_CONTEXT_SYSTEM_AWARE,
-  DPI_AWARENESS_CONTEXT_UNAWARE,
-  DPI_AWARENESS_CONTEXT_UNAWARE_GDISCALED,
-  GetDpiForSystem,
-  SetProcessDpiAwarenessContext,
-};
 pub use winmain::*;
+use {
+  crate::{
+    Rect,
+    get_last_error,
+    input::scroll::{
+      ScrollConfig,
+      WheelScroll,
+    },
+    last_error,
+    reset_last_error,
+  },
+  std::{
+    ffi::c_void,
+    time::Duration,
+  },
+  widestring::U16CString,
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::{
+    Foundation::RECT,
+    UI::{
+      Accessibility::{
+        HCF_HIGHCONTRASTON,
+        HIGHCONTRASTW,
+      },
+      HiDpi::{
+        DPI_AWARENESS_CONTEXT,
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        DPI_AWARENESS_CONTEXT_SYSTEM_AWARE,
+        DPI_AWARENESS_CONTEXT_UNAWARE,
+        DPI_AWARENESS_CONTEXT_UNAWARE_GDISCALED,
+        GetDpiForSystem,
+        GetSystemMetricsForDpi,
+        SetProcessDpiAwarenessContext,
+      },
+      Input::KeyboardAndMouse::GetDoubleClickTime,
+      WindowsAndMessaging::{
+        self,
+        ANIMATIONINFO,
+        GetCaretBlinkTime,
+        GetSystemMetrics,
+        SPI_GETANIMATION,
+        SPI_GETCLIENTAREAANIMATION,
+        SPI_GETHIGHCONTRAST,
+        SPI_GETWHEELSCROLLCHARS,
+        SPI_GETWHEELSCROLLLINES,
+        SPI_GETWORKAREA,
+        SPI_SETANIMATION,
+        SPI_SETCLIENTAREAANIMATION,
+        SPI_SETDOUBLECLICKTIME,
+        SPI_SETHIGHCONTRAST,
+        SPI_SETWORKAREA,
+        SPIF_SENDCHANGE,
+        SPIF_UPDATEINIFILE,
+        SYSTEM_METRICS_INDEX,
+        SYSTEM_PARAMETERS_INFO_ACTION,
+        SetCaretBlinkTime,
+        SystemParametersInfoW,
+      },
+    },
+  },
+};
 
 pub mod message;
 pub use message::*;
@@ -36,6 +91,9 @@ pub use message::*;
 pub mod message_loop;
 pub use message_loop::*;
 
+pub mod panic;
+pub use panic::*;
+
 pub mod monitor;
 pub use monitor::*;
 
@@ -48,6 +106,12 @@ pub use flags::*;
 pub mod cursor;
 pub use cursor::*;
 
+pub mod decorations;
+pub use decorations::*;
+
+pub mod icon;
+pub use icon::*;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DPIAwarenessContext {
   Unaware,
@@ -84,3 +148,400 @@ pub fn system_dpi() -> u32 {
 pub fn dpi_to_scale_factor(dpi: u32) -> f64 {
   dpi as f64 / Monitor::BASE_DPI as f64
 }
+
+/// A value read with `GetSystemMetrics`. Sizes are in pixels.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getsystemmetrics"]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SystemMetric {
+  /// `SM_CXSCREEN`: Width of the primary monitor.
+  ScreenWidth,
+  /// `SM_CYSCREEN`
+  ScreenHeight,
+  /// `SM_XVIRTUALSCREEN`: Left edge of the rectangle bounding every monitor.
+  VirtualScreenX,
+  /// `SM_YVIRTUALSCREEN`
+  VirtualScreenY,
+  /// `SM_CXVIRTUALSCREEN`
+  VirtualScreenWidth,
+  /// `SM_CYVIRTUALSCREEN`
+  VirtualScreenHeight,
+  /// `SM_CXFULLSCREEN`: Client area of a maximized window on the primary monitor.
+  FullscreenClientWidth,
+  /// `SM_CYFULLSCREEN`
+  FullscreenClientHeight,
+  /// `SM_CXMAXIMIZED`
+  MaximizedWidth,
+  /// `SM_CYMAXIMIZED`
+  MaximizedHeight,
+  /// `SM_CXMAXTRACK`
+  MaxTrackWidth,
+  /// `SM_CYMAXTRACK`
+  MaxTrackHeight,
+  /// `SM_CXMINTRACK`
+  MinTrackWidth,
+  /// `SM_CYMINTRACK`
+  MinTrackHeight,
+  /// `SM_CXMIN`
+  MinWidth,
+  /// `SM_CYMIN`
+  MinHeight,
+  /// `SM_CXMINIMIZED`
+  MinimizedWidth,
+  /// `SM_CYMINIMIZED`
+  MinimizedHeight,
+  /// `SM_CXBORDER`
+  BorderWidth,
+  /// `SM_CYBORDER`
+  BorderHeight,
+  /// `SM_CXEDGE`: 3D border.
+  EdgeWidth,
+  /// `SM_CYEDGE`
+  EdgeHeight,
+  /// `SM_CXFIXEDFRAME`: Frame of a window that has a caption but can't be resized.
+  FixedFrameWidth,
+  /// `SM_CYFIXEDFRAME`
+  FixedFrameHeight,
+  /// `SM_CXSIZEFRAME`: Frame of a window that can be resized.
+  SizeFrameWidth,
+  /// `SM_CYSIZEFRAME`
+  SizeFrameHeight,
+  /// `SM_CXFOCUSBORDER`
+  FocusBorderWidth,
+  /// `SM_CYFOCUSBORDER`
+  FocusBorderHeight,
+  /// `SM_CXPADDEDBORDER`: Padding added to the sizing frame of captioned windows.
+  PaddedBorderWidth,
+  /// `SM_CYCAPTION`
+  CaptionHeight,
+  /// `SM_CYSMCAPTION`
+  SmallCaptionHeight,
+  /// `SM_CXSIZE`
+  CaptionButtonWidth,
+  /// `SM_CYSIZE`
+  CaptionButtonHeight,
+  /// `SM_CXSMSIZE`
+  SmallCaptionButtonWidth,
+  /// `SM_CYSMSIZE`
+  SmallCaptionButtonHeight,
+  /// `SM_CYMENU`: Single-line menu bar.
+  MenuHeight,
+  /// `SM_CXMENUSIZE`
+  MenuButtonWidth,
+  /// `SM_CYMENUSIZE`
+  MenuButtonHeight,
+  /// `SM_CXMENUCHECK`
+  MenuCheckWidth,
+  /// `SM_CYMENUCHECK`
+  MenuCheckHeight,
+  /// `SM_CXICON`
+  IconWidth,
+  /// `SM_CYICON`
+  IconHeight,
+  /// `SM_CXSMICON`
+  SmallIconWidth,
+  /// `SM_CYSMICON`
+  SmallIconHeight,
+  /// `SM_CXICONSPACING`
+  IconSpacingWidth,
+  /// `SM_CYICONSPACING`
+  IconSpacingHeight,
+  /// `SM_CXCURSOR`
+  CursorWidth,
+  /// `SM_CYCURSOR`
+  CursorHeight,
+  /// `SM_CXVSCROLL`
+  VerticalScrollBarWidth,
+  /// `SM_CYVSCROLL`
+  VerticalScrollArrowHeight,
+  /// `SM_CYVTHUMB`
+  VerticalScrollThumbHeight,
+  /// `SM_CYHSCROLL`
+  HorizontalScrollBarHeight,
+  /// `SM_CXHSCROLL`
+  HorizontalScrollArrowWidth,
+  /// `SM_CXHTHUMB`
+  HorizontalScrollThumbWidth,
+  /// `SM_CXDOUBLECLK`: Width of the rectangle the second click of a double click has to land in.
+  DoubleClickWidth,
+  /// `SM_CYDOUBLECLK`
+  DoubleClickHeight,
+  /// `SM_CXDRAG`: How far the mouse has to move horizontally before a drag starts.
+  DragWidth,
+  /// `SM_CYDRAG`
+  DragHeight,
+  /// `SM_CMONITORS`
+  MonitorCount,
+  /// `SM_CMOUSEBUTTONS`: Zero when there is no mouse.
+  MouseButtonCount,
+  /// `SM_MOUSEPRESENT`
+  MousePresent,
+  /// `SM_MOUSEWHEELPRESENT`
+  MouseWheelPresent,
+  /// `SM_MOUSEHORIZONTALWHEELPRESENT`
+  MouseHorizontalWheelPresent,
+  /// `SM_SWAPBUTTON`: Nonzero when the left and right mouse buttons are swapped.
+  SwapButton,
+  /// `SM_DIGITIZER`
+  Digitizer,
+  /// `SM_MAXIMUMTOUCHES`
+  MaximumTouches,
+  /// `SM_REMOTESESSION`
+  RemoteSession,
+  /// `SM_CONVERTIBLESLATEMODE`: Zero when a convertible device is in slate mode.
+  ConvertibleSlateMode,
+  /// `SM_SYSTEMDOCKED`
+  SystemDocked,
+  /// `SM_SHUTTINGDOWN`
+  ShuttingDown,
+  /// `SM_IMMENABLED`
+  ImmEnabled,
+  /// `SM_MENUDROPALIGNMENT`: Nonzero when drop-down menus are right-aligned with their menu bar item.
+  MenuDropAlignment,
+  /// `SM_SAMEDISPLAYFORMAT`
+  SameDisplayFormat,
+  /// `SM_SHOWSOUNDS`
+  ShowSounds,
+}
+
+impl SystemMetric {
+  pub const fn to_raw(self) -> SYSTEM_METRICS_INDEX {
+    match self {
+      Self::ScreenWidth => WindowsAndMessaging::SM_CXSCREEN,
+      Self::ScreenHeight => WindowsAndMessaging::SM_CYSCREEN,
+      Self::VirtualScreenX => WindowsAndMessaging::SM_XVIRTUALSCREEN,
+      Self::VirtualScreenY => WindowsAndMessaging::SM_YVIRTUALSCREEN,
+      Self::VirtualScreenWidth => WindowsAndMessaging::SM_CXVIRTUALSCREEN,
+      Self::VirtualScreenHeight => WindowsAndMessaging::SM_CYVIRTUALSCREEN,
+      Self::FullscreenClientWidth => WindowsAndMessaging::SM_CXFULLSCREEN,
+      Self::FullscreenClientHeight => WindowsAndMessaging::SM_CYFULLSCREEN,
+      Self::MaximizedWidth => WindowsAndMessaging::SM_CXMAXIMIZED,
+      Self::MaximizedHeight => WindowsAndMessaging::SM_CYMAXIMIZED,
+      Self::MaxTrackWidth => WindowsAndMessaging::SM_CXMAXTRACK,
+      Self::MaxTrackHeight => WindowsAndMessaging::SM_CYMAXTRACK,
+      Self::MinTrackWidth => WindowsAndMessaging::SM_CXMINTRACK,
+      Self::MinTrackHeight => WindowsAndMessaging::SM_CYMINTRACK,
+      Self::MinWidth => WindowsAndMessaging::SM_CXMIN,
+      Self::MinHeight => WindowsAndMessaging::SM_CYMIN,
+      Self::MinimizedWidth => WindowsAndMessaging::SM_CXMINIMIZED,
+      Self::MinimizedHeight => WindowsAndMessaging::SM_CYMINIMIZED,
+      Self::BorderWidth => WindowsAndMessaging::SM_CXBORDER,
+      Self::BorderHeight => WindowsAndMessaging::SM_CYBORDER,
+      Self::EdgeWidth => WindowsAndMessaging::SM_CXEDGE,
+      Self::EdgeHeight => WindowsAndMessaging::SM_CYEDGE,
+      Self::FixedFrameWidth => WindowsAndMessaging::SM_CXFIXEDFRAME,
+      Self::FixedFrameHeight => WindowsAndMessaging::SM_CYFIXEDFRAME,
+      Self::SizeFrameWidth => WindowsAndMessaging::SM_CXSIZEFRAME,
+      Self::SizeFrameHeight => WindowsAndMessaging::SM_CYSIZEFRAME,
+      Self::FocusBorderWidth => WindowsAndMessaging::SM_CXFOCUSBORDER,
+      Self::FocusBorderHeight => WindowsAndMessaging::SM_CYFOCUSBORDER,
+      Self::PaddedBorderWidth => WindowsAndMessaging::SM_CXPADDEDBORDER,
+      Self::CaptionHeight => WindowsAndMessaging::SM_CYCAPTION,
+      Self::SmallCaptionHeight => WindowsAndMessaging::SM_CYSMCAPTION,
+      Self::CaptionButtonWidth => WindowsAndMessaging::SM_CXSIZE,
+      Self::CaptionButtonHeight => WindowsAndMessaging::SM_CYSIZE,
+      Self::SmallCaptionButtonWidth => WindowsAndMessaging::SM_CXSMSIZE,
+      Self::SmallCaptionButtonHeight => WindowsAndMessaging::SM_CYSMSIZE,
+      Self::MenuHeight => WindowsAndMessaging::SM_CYMENU,
+      Self::MenuButtonWidth => WindowsAndMessaging::SM_CXMENUSIZE,
+      Self::MenuButtonHeight => WindowsAndMessaging::SM_CYMENUSIZE,
+      Self::MenuCheckWidth => WindowsAndMessaging::SM_CXMENUCHECK,
+      Self::MenuCheckHeight => WindowsAndMessaging::SM_CYMENUCHECK,
+      Self::IconWidth => WindowsAndMessaging::SM_CXICON,
+      Self::IconHeight => WindowsAndMessaging::SM_CYICON,
+      Self::SmallIconWidth => WindowsAndMessaging::SM_CXSMICON,
+      Self::SmallIconHeight => WindowsAndMessaging::SM_CYSMICON,
+      Self::IconSpacingWidth => WindowsAndMessaging::SM_CXICONSPACING,
+      Self::IconSpacingHeight => WindowsAndMessaging::SM_CYICONSPACING,
+      Self::CursorWidth => WindowsAndMessaging::SM_CXCURSOR,
+      Self::CursorHeight => WindowsAndMessaging::SM_CYCURSOR,
+      Self::VerticalScrollBarWidth => WindowsAndMessaging::SM_CXVSCROLL,
+      Self::VerticalScrollArrowHeight => WindowsAndMessaging::SM_CYVSCROLL,
+      Self::VerticalScrollThumbHeight => WindowsAndMessaging::SM_CYVTHUMB,
+      Self::HorizontalScrollBarHeight => WindowsAndMessaging::SM_CYHSCROLL,
+      Self::HorizontalScrollArrowWidth => WindowsAndMessaging::SM_CXHSCROLL,
+      Self::HorizontalScrollThumbWidth => WindowsAndMessaging::SM_CXHTHUMB,
+      Self::DoubleClickWidth => WindowsAndMessaging::SM_CXDOUBLECLK,
+      Self::DoubleClickHeight => WindowsAndMessaging::SM_CYDOUBLECLK,
+      Self::DragWidth => WindowsAndMessaging::SM_CXDRAG,
+      Self::DragHeight => WindowsAndMessaging::SM_CYDRAG,
+      Self::MonitorCount => WindowsAndMessaging::SM_CMONITORS,
+      Self::MouseButtonCount => WindowsAndMessaging::SM_CMOUSEBUTTONS,
+      Self::MousePresent => WindowsAndMessaging::SM_MOUSEPRESENT,
+      Self::MouseWheelPresent => WindowsAndMessaging::SM_MOUSEWHEELPRESENT,
+      Self::MouseHorizontalWheelPresent => WindowsAndMessaging::SM_MOUSEHORIZONTALWHEELPRESENT,
+      Self::SwapButton => WindowsAndMessaging::SM_SWAPBUTTON,
+      Self::Digitizer => WindowsAndMessaging::SM_DIGITIZER,
+      Self::MaximumTouches => WindowsAndMessaging::SM_MAXIMUMTOUCHES,
+      Self::RemoteSession => WindowsAndMessaging::SM_REMOTESESSION,
+      Self::ConvertibleSlateMode => WindowsAndMessaging::SM_CONVERTIBLESLATEMODE,
+      Self::SystemDocked => WindowsAndMessaging::SM_SYSTEMDOCKED,
+      Self::ShuttingDown => WindowsAndMessaging::SM_SHUTTINGDOWN,
+      Self::ImmEnabled => WindowsAndMessaging::SM_IMMENABLED,
+      Self::MenuDropAlignment => WindowsAndMessaging::SM_MENUDROPALIGNMENT,
+      Self::SameDisplayFormat => WindowsAndMessaging::SM_SAMEDISPLAYFORMAT,
+      Self::ShowSounds => WindowsAndMessaging::SM_SHOWSOUNDS,
+    }
+  }
+}
+
+/// Reads a metric as seen by the calling thread, which is scaled for the system DPI unless the thread
+/// is DPI unaware. Zero can mean either failure or a value of zero.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getsystemmetrics"]
+pub fn system_metric(metric: SystemMetric) -> i32 {
+  unsafe { GetSystemMetrics(metric.to_raw()) }
+}
+
+/// Reads a metric scaled for `dpi`, regardless of the DPI awareness of the calling thread. Use this
+/// for the monitor a per-monitor aware window is on, e.g. with [`Window::dpi`].
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getsystemmetricsfordpi"]
+pub fn system_metric_for_dpi(metric: SystemMetric, dpi: u32) -> i32 {
+  unsafe { GetSystemMetricsForDpi(metric.to_raw(), dpi) }
+}
+
+fn get_system_parameter<T: Default>(action: SYSTEM_PARAMETERS_INFO_ACTION) -> Result<T> {
+  let mut value = T::default();
+  reset_last_error();
+  match unsafe { SystemParametersInfoW(action, 0, (&raw mut value).cast(), 0) } {
+    0 => Err(get_last_error().unwrap_or(Error::empty())),
+    _ => Ok(value),
+  }
+}
+
+/// Setters write the change to the user profile and broadcast `WM_SETTINGCHANGE`, the same as
+/// changing it in the control panel.
+fn set_system_parameter(action: SYSTEM_PARAMETERS_INFO_ACTION, param: u32, value: *mut c_void) -> Result<()> {
+  reset_last_error();
+  match unsafe { SystemParametersInfoW(action, param, value, SPIF_UPDATEINIFILE | SPIF_SENDCHANGE) } {
+    0 => last_error(),
+    _ => Ok(()),
+  }
+}
+
+/// Reads how far one notch of the mouse wheel should scroll.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-systemparametersinfow"]
+pub fn scroll_config() -> Result<ScrollConfig> {
+  let lines = get_system_parameter::<u32>(SPI_GETWHEELSCROLLLINES)?;
+  let chars = get_system_parameter::<u32>(SPI_GETWHEELSCROLLCHARS)?;
+  Ok(ScrollConfig { vertical: WheelScroll::from_raw(lines), horizontal_chars: chars })
+}
+
+/// The part of the primary monitor that isn't covered by the taskbar or docked toolbars, in
+/// physical pixels for DPI aware threads. See [`Monitor`] for other monitors.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-systemparametersinfow"]
+pub fn work_area() -> Result<Rect> {
+  get_system_parameter::<RECT>(SPI_GETWORKAREA).map(Rect::from)
+}
+
+pub fn set_work_area(area: Rect) -> Result<()> {
+  let mut area = area.to_raw();
+  set_system_parameter(SPI_SETWORKAREA, 0, (&raw mut area).cast())
+}
+
+/// The longest time between the clicks of a double click.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getdoubleclicktime"]
+pub fn double_click_time() -> Duration {
+  Duration::from_millis(unsafe { GetDoubleClickTime() } as u64)
+}
+
+/// Times over 5 seconds are clamped to 5 seconds. Zero resets it to the default of 500 ms.
+pub fn set_double_click_time(time: Duration) -> Result<()> {
+  let millis = time.as_millis().min(u32::MAX as u128) as u32;
+  set_system_parameter(SPI_SETDOUBLECLICKTIME, millis, std::ptr::null_mut())
+}
+
+/// Returns `None` when the caret doesn't blink.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getcaretblinktime"]
+pub fn caret_blink_time() -> Result<Option<Duration>> {
+  reset_last_error();
+  match unsafe { GetCaretBlinkTime() } {
+    0 => Err(get_last_error().unwrap_or(Error::empty())),
+    u32::MAX => Ok(None),
+    millis => Ok(Some(Duration::from_millis(millis as u64))),
+  }
+}
+
+/// Unlike the other setters, this only lasts until the user logs off and isn't broadcast.
+pub fn set_caret_blink_time(time: Option<Duration>) -> Result<()> {
+  let millis = match time {
+    Some(time) => time.as_millis().min(u32::MAX as u128 - 1) as u32,
+    None => u32::MAX,
+  };
+  reset_last_error();
+  match unsafe { SetCaretBlinkTime(millis) } {
+    0 => last_error(),
+    _ => Ok(()),
+  }
+}
+
+/// Whether windows animate when they are minimized and restored.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-systemparametersinfow"]
+pub fn animations_enabled() -> Result<bool> {
+  let mut info = ANIMATIONINFO { cbSize: size_of::<ANIMATIONINFO>() as u32, iMinAnimate: 0 };
+  reset_last_error();
+  match unsafe { SystemParametersInfoW(SPI_GETANIMATION, info.cbSize, (&raw mut info).cast(), 0) } {
+    0 => Err(get_last_error().unwrap_or(Error::empty())),
+    _ => Ok(info.iMinAnimate != 0),
+  }
+}
+
+pub fn set_animations_enabled(enabled: bool) -> Result<()> {
+  let mut info = ANIMATIONINFO { cbSize: size_of::<ANIMATIONINFO>() as u32, iMinAnimate: enabled as i32 };
+  set_system_parameter(SPI_SETANIMATION, info.cbSize, (&raw mut info).cast())
+}
+
+/// Whether the user turned off "Show animations in Windows". Apps are expected to skip
+/// non-essential animations inside their client area when this is set.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-systemparametersinfow"]
+pub fn reduced_motion() -> Result<bool> {
+  get_system_parameter::<i32>(SPI_GETCLIENTAREAANIMATION).map(|animate| animate == 0)
+}
+
+pub fn set_reduced_motion(reduced: bool) -> Result<()> {
+  // The new value is passed as the pointer itself.
+  set_system_parameter(SPI_SETCLIENTAREAANIMATION, 0, (!reduced) as usize as *mut c_void)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HighContrast {
+  pub enabled: bool,
+  /// The name of the color scheme used while high contrast is on, e.g. "High Contrast Black".
+  pub scheme: Option<String>,
+}
+
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-highcontrastw"]
+pub fn high_contrast() -> Result<HighContrast> {
+  let mut info = HIGHCONTRASTW { cbSize: size_of::<HIGHCONTRASTW>() as u32, ..Default::default() };
+  reset_last_error();
+  match unsafe { SystemParametersInfoW(SPI_GETHIGHCONTRAST, info.cbSize, (&raw mut info).cast(), 0) } {
+    0 => Err(get_last_error().unwrap_or(Error::empty())),
+    _ => Ok(HighContrast {
+      enabled: (info.dwFlags & HCF_HIGHCONTRASTON) != 0,
+      scheme: (!info.lpszDefaultScheme.is_null())
+        .then(|| unsafe { U16CString::from_ptr_str(info.lpszDefaultScheme) }.to_string_lossy()),
+    }),
+  }
+}
+
+/// Leaving `scheme` as `None` keeps the current scheme.
+pub fn set_high_contrast(high_contrast: &HighContrast) -> Result<()> {
+  let mut scheme =
+    high_contrast.scheme.as_deref().map(U16CString::from_str_truncate).map(U16CString::into_vec_with_nul);
+  let mut info = HIGHCONTRASTW {
+    cbSize: size_of::<HIGHCONTRASTW>() as u32,
+    dwFlags: match high_contrast.enabled {
+      true => HCF_HIGHCONTRASTON,
+      false => 0,
+    },
+    lpszDefaultScheme: scheme.as_mut().map_or(std::ptr::null_mut(), |scheme| scheme.as_mut_ptr()),
+  };
+  set_system_parameter(SPI_SETHIGHCONTRAST, info.cbSize, (&raw mut info).cast())
+}
@@ -9,6 +9,7 @@ use {
   super::{
     Brush,
     Class,
+    Icon,
     Instance,
     LoadCursor,
     NoProc,
@@ -82,6 +83,7 @@ pub struct WindowClassBuilder<N> {
   style: WindowClassStyle,
   instance: Instance,
   background: Brush,
+  icon: (Option<Icon>, Option<Icon>),
 }
 
 impl Default for WindowClassBuilder<NoName> {
@@ -91,6 +93,7 @@ impl Default for WindowClassBuilder<NoName> {
       style: WindowClassStyle::default(),
       instance: Instance::get(),
       background: Brush::default(),
+      icon: (None, None),
     }
   }
 }
@@ -102,6 +105,7 @@ impl WindowClassBuilder<NoName> {
       style: self.style,
       instance: self.instance,
       background: self.background,
+      icon: self.icon,
     }
   }
 }
@@ -121,6 +125,13 @@ impl<N> WindowClassBuilder<N> {
     self.background = brush;
     self
   }
+
+  /// The default taskbar (`big`) and title bar (`small`) icons of windows of this class. Windows scales
+  /// `big` down when `small` is `None`. The icons have to outlive the class.
+  pub fn with_icon(mut self, big: Option<Icon>, small: Option<Icon>) -> Self {
+    self.icon = (big, small);
+    self
+  }
 }
 
 impl WindowClassBuilder<Name<'_>> {
@@ -134,6 +145,8 @@ impl WindowClassBuilder<Name<'_>> {
       lpfnWndProc: Some(window_procedure),
       style: self.style.to_raw(),
       hCursor: CursorIcon::Default.load(),
+      hIcon: self.icon.0.unwrap_or_default().to_ptr(),
+      hIconSm: self.icon.1.unwrap_or_default().to_ptr(),
       hbrBackground: self.background.to_ptr(),
       ..Default::default()
     };
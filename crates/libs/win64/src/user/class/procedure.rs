@@ -2,6 +2,10 @@ use {
   super::LResult,
   crate::{
     Handle,
+    input::{
+      keyboard::layout::LayoutCache,
+      scroll::WheelAccumulator,
+    },
     user::{
       LParam,
       Message,
@@ -9,9 +13,23 @@ use {
       WParam,
       Window,
       WindowPtrIndex,
+      catch_procedure_panic,
+      decorations::DecorationsLayout,
+      fullscreen::FullscreenState,
+      hwnd::cursor::CursorState,
       register_window_thread_id,
     },
   },
+  std::{
+    cell::{
+      Cell,
+      RefCell,
+    },
+    collections::VecDeque,
+    ops::Deref,
+    rc::Rc,
+    sync::Arc,
+  },
   windows_sys::Win32::Foundation::{
     HWND,
     LPARAM,
@@ -20,6 +38,22 @@ use {
   },
 };
 
+/// Handles the messages of a window.
+///
+/// Anything `on_message` calls that sends a message to the same window, e.g. `send_message`, `destroy` or
+/// `set_position`, re-enters the window procedure while `on_message` is still running. Those nested
+/// messages can't be handed to `on_message` without aliasing `self`, so they get the default handling
+/// instead.
+///
+/// Nested messages that only carry plain values are handed to `on_message` once it returns, in the order
+/// they arrived, and their result is ignored. Those are `Activate`, `ActivateApp`, `CaptureChanged`,
+/// `Close`, `Destroy`, `Enable`, `EnterSizeMove`, `ExitSizeMove`, `KillFocus`, `Move`, `NcDestroy`,
+/// `SetFocus`, `ShowWindow` and `Size`. A nested `NcDestroy` means the window is gone by the time any of
+/// them are handed over, so only `Destroy` and `NcDestroy` itself are kept from the queue then.
+///
+/// Every other nested message, e.g. `Paint`, `WindowPosChanging`, `GetMinMaxInfo` or a user message sent
+/// with `send_message`, only gets the default handling and never reaches `on_message`. Post messages to
+/// the window instead of sending them to have them handled, or give the window a [`NestedProcedure`].
 #[allow(unused_variables)]
 pub trait WindowProcedure {
   fn on_message(&mut self, window: &Window, message: &Message) -> Option<LResult> {
@@ -27,6 +61,98 @@ pub trait WindowProcedure {
   }
 }
 
+/// Handles the messages sent to a window while its [`WindowProcedure`] is busy. See
+/// [`Window::set_nested_procedure`].
+///
+/// Nested messages get here before they are queued up for the window procedure, and while they can still
+/// be answered, so it sees the ones with pointers too, e.g. `NcCalcSize`, `GetMinMaxInfo`,
+/// `WindowPosChanging` or a user message sent with `send_message`. Messages it returns a result for are
+/// neither queued up nor given the default handling. It only gets `&self`, so any state it shares with
+/// the window procedure has to be behind a `Cell` or `RefCell` both of them hold, e.g. in an `Rc`.
+pub trait NestedProcedure {
+  fn on_nested_message(&self, window: &Window, message: &Message) -> Option<LResult>;
+}
+
+impl<F: Fn(&Window, &Message) -> Option<LResult>> NestedProcedure for F {
+  fn on_nested_message(&self, window: &Window, message: &Message) -> Option<LResult> {
+    self(window, message)
+  }
+}
+
+/// A window procedure that is borrowed while it handles a message, the nested messages it hasn't seen yet,
+/// and what handles them in the meantime.
+pub(crate) struct ProcedureCell {
+  proc: RefCell<Box<dyn WindowProcedure>>,
+  deferred: RefCell<VecDeque<Message>>,
+  nested: RefCell<Option<Rc<dyn NestedProcedure>>>,
+}
+
+impl ProcedureCell {
+  pub fn new(proc: Box<dyn WindowProcedure>) -> Self {
+    Self { proc: RefCell::new(proc), deferred: RefCell::new(VecDeque::new()), nested: RefCell::new(None) }
+  }
+
+  pub fn set_nested(&self, nested: Option<Rc<dyn NestedProcedure>>) {
+    *self.nested.borrow_mut() = nested;
+  }
+
+  /// Hands `message` to the procedure, or to the nested procedure if the procedure is busy, and queues it
+  /// up for later if that leaves it alone. Returns `None` for nested messages nothing answered, so they
+  /// get the default handling, and for messages a procedure panicked on.
+  pub fn dispatch(&self, window: &Window, message: &Message) -> Option<LResult> {
+    let Ok(mut proc) = self.proc.try_borrow_mut() else {
+      // Cloned out, so the nested procedure can replace itself.
+      let nested = self.nested.borrow().clone();
+      if let Some(result) = nested
+        .and_then(|nested| catch_procedure_panic(window, || nested.on_nested_message(window, message)))
+        .flatten()
+      {
+        return Some(result);
+      }
+      let mut deferred = self.deferred.borrow_mut();
+      if let Message::NcDestroy = message {
+        deferred.retain(|deferred| matches!(deferred, Message::Destroy));
+      }
+      if is_deferrable(message) {
+        deferred.push_back(message.clone());
+      }
+      return None;
+    };
+    let result = catch_procedure_panic(window, || proc.on_message(window, message)).flatten();
+    // Handling a deferred message may nest and defer more, so the queue is only borrowed to pop.
+    loop {
+      let Some(deferred) = self.deferred.borrow_mut().pop_front() else {
+        break;
+      };
+      catch_procedure_panic(window, || proc.on_message(window, &deferred));
+    }
+    result
+  }
+}
+
+/// Whether `message` still means the same once its sender has returned, since its parameters don't point
+/// to anything.
+fn is_deferrable(message: &Message) -> bool {
+  matches!(
+    message,
+    Message::Activate(_)
+      | Message::ActivateApp(_)
+      | Message::CaptureChanged(_)
+      | Message::Close
+      | Message::Destroy
+      | Message::Enable(_)
+      | Message::EnterSizeMove
+      | Message::ExitSizeMove
+      | Message::KillFocus(_)
+      | Message::Move(_)
+      | Message::NcDestroy
+      | Message::SetFocus(_)
+      | Message::ShowWindow(_)
+      | Message::Size(_)
+  )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum WindowState {
   Creating,
   Running,
@@ -34,13 +160,69 @@ pub(crate) enum WindowState {
 }
 
 pub(crate) struct UserData {
-  pub proc: Box<dyn WindowProcedure>,
-  pub state: WindowState,
+  pub proc: ProcedureCell,
+  pub state: Cell<WindowState>,
+  /// Set on `NcDestroy`. The user data is freed once the last [`UserDataGuard`] lets go of it.
+  pub destroyed: Cell<bool>,
+  /// Kept in a `Cell` so it can be reached while `proc` is borrowed by the running handler.
+  pub wheel: Cell<WheelAccumulator>,
+  pub fullscreen: Cell<Option<FullscreenState>>,
+  pub cursor: Cell<CursorState>,
+  /// The title bar last seen by the procedure's [`Decorations`](crate::user::Decorations), for the
+  /// messages it can't be asked about. Only borrowed for as long as it takes to read or replace it.
+  pub decorations: RefCell<Option<Arc<DecorationsLayout>>>,
+  /// How many [`UserDataGuard`]s are alive, i.e. how many frames up the stack are using the user data.
+  depth: Cell<u32>,
 }
 
 impl UserData {
   pub fn new(proc: Box<dyn WindowProcedure>) -> Self {
-    Self { proc, state: WindowState::Creating }
+    Self {
+      proc: ProcedureCell::new(proc),
+      state: Cell::new(WindowState::Creating),
+      destroyed: Cell::new(false),
+      wheel: Cell::new(WheelAccumulator::new()),
+      fullscreen: Cell::new(None),
+      cursor: Cell::new(CursorState::default()),
+      decorations: RefCell::new(None),
+      depth: Cell::new(0),
+    }
+  }
+}
+
+/// Keeps a window's user data alive while it is in use.
+///
+/// Anything that sends a message to the window can destroy it on the way, so the user data is only ever
+/// reached through one of these. Once the window has been destroyed, the last guard to go frees the data.
+pub(crate) struct UserDataGuard {
+  data: *mut UserData,
+}
+
+impl UserDataGuard {
+  /// # Safety
+  /// `data` has to be a window's user data, made by `Box::into_raw` and not freed yet.
+  pub unsafe fn enter(data: *mut UserData) -> Self {
+    let depth = unsafe { &(*data).depth };
+    depth.set(depth.get() + 1);
+    Self { data }
+  }
+}
+
+impl Deref for UserDataGuard {
+  type Target = UserData;
+
+  fn deref(&self) -> &UserData {
+    unsafe { &*self.data }
+  }
+}
+
+impl Drop for UserDataGuard {
+  fn drop(&mut self) {
+    let depth = self.depth.get() - 1;
+    self.depth.set(depth);
+    if depth == 0 && self.destroyed.get() {
+      drop(unsafe { Box::from_raw(self.data) });
+    }
   }
 }
 
@@ -54,33 +236,195 @@ pub(crate) unsafe extern "system" fn window_procedure(
 ) -> LRESULT {
   let window = unsafe { Window::from_ptr(hwnd) };
   let message = Message::new(msg.into(), WParam(w_param), LParam(l_param));
-  let result =
-    on_message(&window, &message).unwrap_or_else(|| window.def_window_proc_raw(msg, w_param, l_param));
+  // The procedure itself is caught in `ProcedureCell::dispatch`, so this only catches the library.
+  let result = catch_procedure_panic(&window, || on_message(&window, &message))
+    .flatten()
+    .unwrap_or_else(|| window.def_window_proc_raw(msg, w_param, l_param));
   result.0
 }
 
 fn on_message(window: &Window, message: &Message) -> Option<LResult> {
-  match (window.user_data(), message) {
-    (None, Message::NcCreate(nc_create_message)) => {
-      register_window_thread_id(*window);
-      nc_create_message.handle(|wnd_proc| {
-        let data_ptr = Box::into_raw(Box::new(UserData::new(wnd_proc)));
-        let _ = window.set_window_ptr(WindowPtrIndex::UserData, data_ptr as isize);
+  let data_ptr = window.get_window_ptr(WindowPtrIndex::UserData) as *mut UserData;
+  if data_ptr.is_null() {
+    let Message::NcCreate(nc_create_message) = message else {
+      return None;
+    };
+    register_window_thread_id(*window);
+    nc_create_message.handle(|wnd_proc| {
+      let data_ptr = Box::into_raw(Box::new(UserData::new(wnd_proc)));
+      let _ = window.set_window_ptr(WindowPtrIndex::UserData, data_ptr as isize);
 
-        unsafe { data_ptr.as_mut() }.expect("window user data ptr went invalid during creation").state =
-          WindowState::Running;
+      unsafe { data_ptr.as_ref() }
+        .expect("window user data ptr went invalid during creation")
+        .state
+        .set(WindowState::Running);
 
-        true
-      });
+      true
+    });
 
-      let data = (unsafe { (window.get_window_ptr(WindowPtrIndex::UserData) as *mut UserData).as_mut() })?;
-      data.proc.on_message(window, message)
+    let data = window.owned_user_data()?;
+    return data.proc.dispatch(window, message);
+  }
+
+  // Held for the whole message, so the handler or anything below can destroy the window without freeing
+  // the user data out from under this frame.
+  let data = unsafe { UserDataGuard::enter(data_ptr) };
+  match message {
+    Message::NcDestroy => {
+      data.destroyed.set(true);
+      // Nothing else gets to the user data from here on, so the guards already out are the last ones.
+      let _ = window.set_window_ptr(WindowPtrIndex::UserData, 0);
+      data.proc.dispatch(window, message)
     },
-    (Some(data), Message::NcDestroy) => {
-      let mut data = unsafe { Box::from_raw(data) };
-      data.proc.on_message(window, message)
+    message => {
+      LayoutCache::update(message);
+      window.update_cursor_clip(message);
+      let result = data
+        .proc
+        .dispatch(window, message)
+        .or_else(|| window.apply_cursor(message))
+        .or_else(|| window.apply_decorations(message));
+      window.update_fullscreen(message);
+      result
     },
-    (Some(data), message) => data.proc.on_message(window, message),
-    (..) => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::user::MessageId,
+  };
+
+  /// Records every message it gets, and sends `nested` to itself while handling `Null`.
+  struct Reentrant {
+    cell: Rc<Cell<*const ProcedureCell>>,
+    nested: Vec<MessageId>,
+    seen: Rc<RefCell<Vec<MessageId>>>,
+    nested_results: Rc<RefCell<Vec<Option<LResult>>>>,
+  }
+
+  impl WindowProcedure for Reentrant {
+    fn on_message(&mut self, window: &Window, message: &Message) -> Option<LResult> {
+      self.seen.borrow_mut().push(message.id());
+      if message.id() == MessageId::Null {
+        let cell = unsafe { &*self.cell.get() };
+        for id in std::mem::take(&mut self.nested) {
+          let result = cell.dispatch(window, &Message::new(id, WParam(0), LParam(0)));
+          self.nested_results.borrow_mut().push(result);
+        }
+      }
+      Some(LResult(1))
+    }
+  }
+
+  /// Dispatches `Null` to a procedure that sends itself `nested` meanwhile, and returns the result of
+  /// `Null`, the messages the procedure saw in order, and the results of the nested messages.
+  fn run(nested: &[MessageId]) -> (Option<LResult>, Vec<MessageId>, Vec<Option<LResult>>) {
+    run_with(nested, None)
+  }
+
+  fn run_with(
+    nested: &[MessageId],
+    nested_procedure: Option<Rc<dyn NestedProcedure>>,
+  ) -> (Option<LResult>, Vec<MessageId>, Vec<Option<LResult>>) {
+    let pointer = Rc::new(Cell::new(std::ptr::null()));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let nested_results = Rc::new(RefCell::new(Vec::new()));
+    let cell = ProcedureCell::new(Box::new(Reentrant {
+      cell: pointer.clone(),
+      nested: nested.to_vec(),
+      seen: seen.clone(),
+      nested_results: nested_results.clone(),
+    }));
+    pointer.set(&cell);
+    cell.set_nested(nested_procedure);
+
+    let window = unsafe { Window::from_ptr(std::ptr::null_mut()) };
+    let result = cell.dispatch(&window, &Message::new(MessageId::Null, WParam(0), LParam(0)));
+    assert!(cell.proc.try_borrow_mut().is_ok());
+    (result, seen.take(), nested_results.take())
+  }
+
+  #[test]
+  fn messages_that_dont_nest_are_handled() {
+    let (result, seen, _) = run(&[]);
+    assert_eq!(result, Some(LResult(1)));
+    assert_eq!(seen, [MessageId::Null]);
+  }
+
+  #[test]
+  fn nested_messages_get_the_default_handling() {
+    let (result, _, nested_results) = run(&[MessageId::Size, MessageId::Paint]);
+    assert_eq!(result, Some(LResult(1)));
+    assert_eq!(nested_results, [None, None]);
+  }
+
+  #[test]
+  fn plain_nested_messages_are_handed_over_afterwards_in_order() {
+    let (_, seen, _) = run(&[MessageId::Size, MessageId::Move, MessageId::Activate]);
+    assert_eq!(seen, [MessageId::Null, MessageId::Size, MessageId::Move, MessageId::Activate]);
+  }
+
+  #[test]
+  fn nested_messages_with_pointers_are_dropped() {
+    let (_, seen, _) = run(&[MessageId::WindowPosChanging, MessageId::Paint, MessageId::Size]);
+    assert_eq!(seen, [MessageId::Null, MessageId::Size]);
+  }
+
+  #[test]
+  fn nested_destruction_keeps_only_destroy() {
+    let (_, seen, _) = run(&[MessageId::Close, MessageId::Size, MessageId::Destroy, MessageId::NcDestroy]);
+    assert_eq!(seen, [MessageId::Null, MessageId::Destroy, MessageId::NcDestroy]);
+  }
+
+  #[test]
+  fn nested_procedure_answers_nested_messages() {
+    let answered = Rc::new(RefCell::new(Vec::new()));
+    let nested_procedure = Rc::new({
+      let answered = answered.clone();
+      move |_: &Window, message: &Message| {
+        answered.borrow_mut().push(message.id());
+        matches!(message, Message::Paint | Message::Size(_)).then_some(LResult(2))
+      }
+    });
+    let (result, seen, nested_results) = run_with(
+      &[MessageId::Paint, MessageId::Size, MessageId::Move, MessageId::NcCalcSize],
+      Some(nested_procedure),
+    );
+    assert_eq!(result, Some(LResult(1)));
+    assert_eq!(answered.take(), [MessageId::Paint, MessageId::Size, MessageId::Move, MessageId::NcCalcSize]);
+    assert_eq!(nested_results, [Some(LResult(2)), Some(LResult(2)), None, None]);
+    // Only what it left alone is handed over afterwards.
+    assert_eq!(seen, [MessageId::Null, MessageId::Move]);
+  }
+
+  /// Sets `dropped` when the user data holding it is freed.
+  struct DropFlag(Rc<Cell<bool>>);
+
+  impl WindowProcedure for DropFlag {}
+
+  impl Drop for DropFlag {
+    fn drop(&mut self) {
+      self.0.set(true);
+    }
+  }
+
+  #[test]
+  fn user_data_outlives_nested_destruction() {
+    let dropped = Rc::new(Cell::new(false));
+    let data = Box::into_raw(Box::new(UserData::new(Box::new(DropFlag(dropped.clone())))));
+
+    let outer = unsafe { UserDataGuard::enter(data) };
+    {
+      // The window is destroyed by a message nested inside the outer one.
+      let nested = unsafe { UserDataGuard::enter(data) };
+      nested.destroyed.set(true);
+    }
+    assert!(!dropped.get());
+    assert!(outer.destroyed.get());
+    drop(outer);
+    assert!(dropped.get());
   }
 }
@@ -1,9 +1,43 @@
 use {
+  crate::{
+    Handle,
+    Rect,
+    declare_handle,
+    get_last_error,
+    last_error,
+    reset_last_error,
+    user::create_icon_indirect,
+  },
   cursor_icon::CursorIcon,
-  windows_sys::Win32::UI::WindowsAndMessaging::{
-    self,
-    HCURSOR,
-    LoadCursorW,
+  dpi::PhysicalPosition,
+  rgb::RGBA8,
+  std::{
+    ops::Deref,
+    path::Path,
+  },
+  widestring::U16CString,
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::{
+    Foundation::{
+      POINT,
+      RECT,
+    },
+    UI::WindowsAndMessaging::{
+      self,
+      ClipCursor,
+      DestroyCursor,
+      GetClipCursor,
+      GetCursorPos,
+      HCURSOR,
+      LoadCursorFromFileW,
+      LoadCursorW,
+      SetCursor,
+      SetCursorPos,
+      ShowCursor,
+    },
   },
 };
 
@@ -68,3 +102,167 @@ impl LoadCursor for CursorIcon {
     load_cursor(self)
   }
 }
+
+declare_handle!(
+  Cursor,
+  alias = "HCURSOR",
+  doc = "https://learn.microsoft.com/en-us/windows/win32/winprog/windows-data-types#hcursor"
+);
+
+impl Cursor {
+  /// One of the system cursors. These are shared, so they must not be destroyed.
+  pub fn system(icon: CursorIcon) -> Self {
+    unsafe { Self::from_ptr(load_cursor(icon)) }
+  }
+
+  /// Only for cursors that were created rather than loaded with `LoadCursorW`. Prefer [`OwnedCursor`],
+  /// which does this on drop.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-destroycursor"]
+  pub fn destroy(self) {
+    unsafe { DestroyCursor(self.to_ptr()) };
+  }
+}
+
+/// A [`Cursor`] that is destroyed when dropped.
+///
+/// Keep this alive for as long as a window uses the cursor.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OwnedCursor(Cursor);
+
+impl Drop for OwnedCursor {
+  fn drop(&mut self) {
+    self.0.destroy();
+  }
+}
+
+impl Deref for OwnedCursor {
+  type Target = Cursor;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+fn cursor_result(cursor: HCURSOR) -> Result<OwnedCursor> {
+  match cursor.is_null() {
+    true => Err(get_last_error().unwrap_or(Error::empty())),
+    false => Ok(OwnedCursor(unsafe { Cursor::from_ptr(cursor) })),
+  }
+}
+
+impl OwnedCursor {
+  /// # Safety
+  /// `cursor` has to be a valid cursor that nothing else destroys, e.g. not a system cursor.
+  pub unsafe fn from_cursor(cursor: Cursor) -> Self {
+    Self(cursor)
+  }
+
+  /// Gives up ownership without destroying the cursor.
+  pub fn into_cursor(self) -> Cursor {
+    let cursor = self.0;
+    std::mem::forget(self);
+    cursor
+  }
+
+  /// Creates a cursor from `width * height` pixels, row by row from the top left. The `hotspot` is the
+  /// pixel that points, e.g. the tip of an arrow. Pixels with an alpha of zero are transparent.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createiconindirect"]
+  pub fn from_rgba(
+    pixels: &[RGBA8],
+    width: u32,
+    height: u32,
+    hotspot: PhysicalPosition<u32>,
+  ) -> Result<Self> {
+    cursor_result(create_icon_indirect(pixels, width, height, Some(hotspot))?)
+  }
+
+  /// Loads a static `.cur` or an animated `.ani` cursor.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-loadcursorfromfilew"]
+  pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+    let path = U16CString::from_os_str_truncate(path.as_ref());
+    reset_last_error();
+    cursor_result(unsafe { LoadCursorFromFileW(path.as_ptr()) })
+  }
+}
+
+/// Changes the cursor right away, until the mouse moves or another window sets its own. `None` hides
+/// the cursor. Returns the previous cursor. For a cursor that sticks, use [`crate::user::Window::set_cursor`].
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setcursor"]
+pub fn set_cursor(cursor: Option<Cursor>) -> Option<Cursor> {
+  let previous = unsafe { SetCursor(cursor.unwrap_or_default().to_ptr()) };
+  match previous.is_null() {
+    true => None,
+    false => Some(unsafe { Cursor::from_ptr(previous) }),
+  }
+}
+
+/// Adds one to (`show`) or takes one from the thread's cursor display counter and returns the new count.
+/// The cursor is shown while the count is zero or more, so every hide has to be matched by a show.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-showcursor"]
+pub fn show_cursor(show: bool) -> i32 {
+  unsafe { ShowCursor(show.into()) }
+}
+
+/// Calls [`show_cursor`] until the cursor is shown or hidden, however unbalanced earlier calls left the
+/// counter.
+pub fn set_cursor_visible(visible: bool) {
+  let mut count = show_cursor(visible);
+  while (count >= 0) != visible {
+    count = show_cursor(visible);
+  }
+}
+
+/// The cursor's position in screen coordinates.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getcursorpos"]
+pub fn cursor_position() -> Result<PhysicalPosition<i32>> {
+  let mut point = POINT::default();
+  reset_last_error();
+  match unsafe { GetCursorPos(&mut point) } {
+    0 => Err(get_last_error().unwrap_or(Error::empty())),
+    _ => Ok(PhysicalPosition::new(point.x, point.y)),
+  }
+}
+
+/// Moves the cursor to a position in screen coordinates.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setcursorpos"]
+pub fn set_cursor_position(position: impl Into<PhysicalPosition<i32>>) -> Result<()> {
+  let position = position.into();
+  reset_last_error();
+  match unsafe { SetCursorPos(position.x, position.y) } {
+    0 => last_error(),
+    _ => Ok(()),
+  }
+}
+
+/// Keeps the cursor inside `rect`, in screen coordinates, or frees it for `None`. Windows drops the
+/// confinement whenever another app clips the cursor or the foreground window changes; see
+/// [`crate::user::Window::confine_cursor`] for confinement that is kept up.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-clipcursor"]
+pub fn clip_cursor(rect: Option<Rect>) -> Result<()> {
+  let rect = rect.map(|rect| rect.to_raw());
+  reset_last_error();
+  match unsafe { ClipCursor(rect.as_ref().map_or(std::ptr::null(), |rect| rect as *const RECT)) } {
+    0 => last_error(),
+    _ => Ok(()),
+  }
+}
+
+/// The rectangle the cursor is confined to, which is the whole virtual screen when it isn't.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getclipcursor"]
+pub fn cursor_clip() -> Result<Rect> {
+  let mut rect = RECT::default();
+  reset_last_error();
+  match unsafe { GetClipCursor(&mut rect) } {
+    0 => Err(get_last_error().unwrap_or(Error::empty())),
+    _ => Ok(rect.into()),
+  }
+}
@@ -0,0 +1,353 @@
+//!
+//! Title bars drawn by the app instead of Windows.
+//!
+//! [`Decorations`] removes the standard title bar while keeping the resize borders, the DWM shadow and
+//! the window animations, and tells Windows which parts of the client area act as the title bar and its
+//! buttons. The app draws all of it.
+//!
+//! Messages sent while the window procedure is busy, e.g. the `NcCalcSize` from maximizing the window,
+//! can't reach [`Decorations`]. For windows created by this crate, the library answers `NcCalcSize` and
+//! `NcHitTest` with the layout the procedure's [`Decorations`] last saw whenever the procedure leaves them
+//! alone, so the title bar stays custom through those too.
+//!
+
+use {
+  crate::{
+    Handle,
+    Rect,
+    signed_high_word,
+    signed_low_word,
+    user::{
+      LParam,
+      LResult,
+      Message,
+      MessageId,
+      NcCalcSizeMessage,
+      NcHitTestMessage,
+      WParam,
+      Window,
+      WindowProcedure,
+    },
+  },
+  dpi::PhysicalPosition,
+  std::sync::Arc,
+  windows_sys::Win32::{
+    Foundation::RECT,
+    Graphics::Gdi::InvalidateRect,
+    UI::{
+      HiDpi::GetSystemMetricsForDpi,
+      Input::KeyboardAndMouse::{
+        TME_LEAVE,
+        TME_NONCLIENT,
+        TRACKMOUSEEVENT,
+        TrackMouseEvent,
+      },
+      WindowsAndMessaging::{
+        self,
+        SC_CLOSE,
+        SC_MAXIMIZE,
+        SC_MINIMIZE,
+        SC_RESTORE,
+        SM_CXPADDEDBORDER,
+        SM_CYFRAME,
+        WM_NCCALCSIZE,
+        WM_NCHITTEST,
+      },
+    },
+  },
+};
+
+/// A button in a custom title bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CaptionButton {
+  Minimize,
+  /// Maximizes or restores the window. Hovering it shows the snap layouts flyout on Windows 11.
+  Maximize,
+  Close,
+}
+
+impl CaptionButton {
+  const fn from_hit_test(hit_test: u32) -> Option<Self> {
+    match hit_test {
+      WindowsAndMessaging::HTMINBUTTON => Some(Self::Minimize),
+      WindowsAndMessaging::HTMAXBUTTON => Some(Self::Maximize),
+      WindowsAndMessaging::HTCLOSE => Some(Self::Close),
+      _ => None,
+    }
+  }
+
+  const fn hit_test(self) -> u32 {
+    match self {
+      Self::Minimize => WindowsAndMessaging::HTMINBUTTON,
+      Self::Maximize => WindowsAndMessaging::HTMAXBUTTON,
+      Self::Close => WindowsAndMessaging::HTCLOSE,
+    }
+  }
+}
+
+/// The layout of a custom title bar, and the message handling that makes Windows treat it as one.
+///
+/// Keep one per window in the window procedure's state, and let it see every message first:
+///
+/// ```ignore
+/// fn on_message(&mut self, window: &Window, message: &Message) -> Option<LResult> {
+///   if let Some(result) = self.decorations.on_message(window, message) {
+///     return Some(result);
+///   }
+///   // ...
+/// }
+/// ```
+///
+/// Or wrap the procedure in [`Decorated`] if the layout never changes. All rectangles are in client
+/// coordinates and physical pixels, so update them on `DpiChanged` and `Size` along with the drawing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Decorations {
+  /// Shared with the window's user data, so the library can answer nested messages with it.
+  layout: Arc<DecorationsLayout>,
+  hovered: Option<CaptionButton>,
+  pressed: Option<CaptionButton>,
+}
+
+/// Where the parts of a custom title bar are.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DecorationsLayout {
+  caption_height: f64,
+  drag_regions: Vec<Rect>,
+  interactive_regions: Vec<Rect>,
+  buttons: Vec<(CaptionButton, Rect)>,
+}
+
+impl Decorations {
+  /// `caption_height` is in logical pixels and is scaled by the window's DPI. The whole strip of that
+  /// height along the top of the client area drags the window.
+  pub fn new(caption_height: f64) -> Self {
+    Self {
+      layout: Arc::new(DecorationsLayout { caption_height, ..Default::default() }),
+      ..Default::default()
+    }
+  }
+
+  pub fn caption_height(&self) -> f64 {
+    self.layout.caption_height
+  }
+
+  pub fn set_caption_height(&mut self, caption_height: f64) {
+    Arc::make_mut(&mut self.layout).caption_height = caption_height;
+  }
+
+  /// Areas outside of the caption strip that drag the window too.
+  pub fn set_drag_regions(&mut self, regions: impl IntoIterator<Item = Rect>) {
+    Arc::make_mut(&mut self.layout).drag_regions = regions.into_iter().collect();
+  }
+
+  /// Areas inside the caption strip or a drag region that get mouse input like the rest of the client
+  /// area, e.g. tabs or a search box in the title bar.
+  pub fn set_interactive_regions(&mut self, regions: impl IntoIterator<Item = Rect>) {
+    Arc::make_mut(&mut self.layout).interactive_regions = regions.into_iter().collect();
+  }
+
+  /// Where the caption buttons are drawn. Buttons that aren't listed don't exist.
+  pub fn set_caption_buttons(&mut self, buttons: impl IntoIterator<Item = (CaptionButton, Rect)>) {
+    Arc::make_mut(&mut self.layout).buttons = buttons.into_iter().collect();
+  }
+
+  /// The button under the cursor, to draw it highlighted.
+  pub fn hovered(&self) -> Option<CaptionButton> {
+    self.hovered
+  }
+
+  /// The button being clicked, to draw it pushed in.
+  pub fn pressed(&self) -> Option<CaptionButton> {
+    self.pressed
+  }
+
+  /// Handles the messages that make up a custom title bar, and returns `None` for everything else.
+  /// Invalidates the window whenever [`Decorations::hovered`] or [`Decorations::pressed`] change.
+  pub fn on_message(&mut self, window: &Window, message: &Message) -> Option<LResult> {
+    self.publish(window);
+    match message {
+      // Have Windows recalculate the frame, since the window was created with the standard one.
+      Message::Create(_) => {
+        let _ = window.frame_changed();
+        None
+      },
+      Message::NcCalcSize(nc_calc_size) => Some(calc_size(window, nc_calc_size)),
+      Message::NcHitTest(nc_hit_test) => self.layout.nc_hit_test(window, nc_hit_test),
+      Message::NcMouseMove(nc_mouse_move) => {
+        let button = CaptionButton::from_hit_test(nc_mouse_move.w.0 as u32);
+        if button.is_some() {
+          let mut track = TRACKMOUSEEVENT {
+            cbSize: size_of::<TRACKMOUSEEVENT>() as u32,
+            dwFlags: TME_LEAVE | TME_NONCLIENT,
+            hwndTrack: window.to_ptr(),
+            dwHoverTime: 0,
+          };
+          unsafe { TrackMouseEvent(&mut track) };
+        }
+        self.set_hovered(window, button);
+        // Keeps the default handling from drawing the classic buttons over the custom ones.
+        button.map(|_| LResult(0))
+      },
+      Message::NcMouseLeave | Message::MouseMove(_) => {
+        self.set_hovered(window, None);
+        self.set_pressed(window, None);
+        None
+      },
+      Message::NcLButtonDown(nc_button) => self.press(window, nc_button.w.0),
+      Message::NcLButtonDblClk(nc_button) => self.press(window, nc_button.w.0),
+      Message::NcLButtonUp(nc_button) => {
+        let button = CaptionButton::from_hit_test(nc_button.w.0 as u32)?;
+        let clicked = self.pressed == Some(button);
+        self.set_pressed(window, None);
+        if clicked {
+          click(window, button);
+        }
+        Some(LResult(0))
+      },
+      _ => None,
+    }
+  }
+
+  fn press(&mut self, window: &Window, hit_test: usize) -> Option<LResult> {
+    let button = CaptionButton::from_hit_test(hit_test as u32)?;
+    self.set_pressed(window, Some(button));
+    Some(LResult(0))
+  }
+
+  /// Hands the layout to the window's user data, unless it has it already.
+  fn publish(&self, window: &Window) {
+    let Some(data) = window.owned_user_data() else {
+      return;
+    };
+    let mut published = data.decorations.borrow_mut();
+    if !published.as_ref().is_some_and(|layout| Arc::ptr_eq(layout, &self.layout)) {
+      *published = Some(self.layout.clone());
+    }
+  }
+
+  fn set_hovered(&mut self, window: &Window, hovered: Option<CaptionButton>) {
+    if self.hovered != hovered {
+      self.hovered = hovered;
+      invalidate(window);
+    }
+  }
+
+  fn set_pressed(&mut self, window: &Window, pressed: Option<CaptionButton>) {
+    if self.pressed != pressed {
+      self.pressed = pressed;
+      invalidate(window);
+    }
+  }
+}
+
+impl DecorationsLayout {
+  fn nc_hit_test(&self, window: &Window, nc_hit_test: &NcHitTestMessage) -> Option<LResult> {
+    let hit_test = window.def_window_proc_raw(WM_NCHITTEST, 0, nc_hit_test.l.0);
+    if hit_test.0 as u32 != WindowsAndMessaging::HTCLIENT {
+      return Some(hit_test);
+    }
+    let l = nc_hit_test.l.0 as i32;
+    let screen = PhysicalPosition::new(signed_low_word(l) as i32, signed_high_word(l) as i32);
+    let position = window.screen_to_client(screen).ok()?;
+    Some(LResult(self.hit_test(window, position) as isize))
+  }
+
+  fn hit_test(&self, window: &Window, position: PhysicalPosition<i32>) -> u32 {
+    if !window.is_maximized() && position.y < resize_border(window) {
+      return WindowsAndMessaging::HTTOP;
+    }
+    if let Some((button, _)) = self.buttons.iter().find(|(_, rect)| rect.contains(position)) {
+      return button.hit_test();
+    }
+    if self.interactive_regions.iter().any(|rect| rect.contains(position)) {
+      return WindowsAndMessaging::HTCLIENT;
+    }
+    let caption_height = (self.caption_height * window.scale_factor()).round() as i32;
+    match position.y < caption_height || self.drag_regions.iter().any(|rect| rect.contains(position)) {
+      true => WindowsAndMessaging::HTCAPTION,
+      false => WindowsAndMessaging::HTCLIENT,
+    }
+  }
+}
+
+impl Window {
+  /// Answers `NcCalcSize` and `NcHitTest` the window procedure left alone with the layout its
+  /// [`Decorations`] last saw, if it has any. Those sent while the procedure is busy always are.
+  pub(crate) fn apply_decorations(&self, message: &Message) -> Option<LResult> {
+    if !matches!(message, Message::NcCalcSize(_) | Message::NcHitTest(_)) {
+      return None;
+    }
+    // Cloned out, so nothing the handling sends finds the user data borrowed.
+    let layout = self.owned_user_data()?.decorations.borrow().clone()?;
+    match message {
+      Message::NcCalcSize(nc_calc_size) => Some(calc_size(self, nc_calc_size)),
+      Message::NcHitTest(nc_hit_test) => layout.nc_hit_test(self, nc_hit_test),
+      _ => None,
+    }
+  }
+}
+
+/// Takes the standard title bar out of the client area calculation, keeping the rest of the frame.
+fn calc_size(window: &Window, nc_calc_size: &NcCalcSizeMessage) -> LResult {
+  // Both forms of the message start with the proposed window rectangle.
+  let rect = unsafe { &mut *(nc_calc_size.l.0 as *mut RECT) };
+  let top = rect.top;
+  // The default handling takes out the standard frame, which keeps the resize borders and the shadow on
+  // the other three sides.
+  window.def_window_proc_raw(WM_NCCALCSIZE, nc_calc_size.w.0, nc_calc_size.l.0);
+  rect.top = top;
+  if window.is_maximized() {
+    // Maximized windows hang over the edges of the monitor by the resize border.
+    rect.top += resize_border(window);
+  }
+  LResult(0)
+}
+
+/// The height of the invisible resize border along the top, for the window's DPI.
+fn resize_border(window: &Window) -> i32 {
+  let dpi = window.dpi();
+  unsafe { GetSystemMetricsForDpi(SM_CYFRAME, dpi) + GetSystemMetricsForDpi(SM_CXPADDEDBORDER, dpi) }
+}
+
+fn invalidate(window: &Window) {
+  unsafe { InvalidateRect(window.to_ptr(), std::ptr::null(), 0) };
+}
+
+/// Goes through `SysCommand` like the standard caption buttons. The command is posted rather than sent,
+/// so the window procedure sees the messages it causes, e.g. `Size` or `Close`, as they happen instead of
+/// nested inside the click.
+fn click(window: &Window, button: CaptionButton) {
+  let command = match button {
+    CaptionButton::Minimize => SC_MINIMIZE,
+    CaptionButton::Maximize if window.is_maximized() => SC_RESTORE,
+    CaptionButton::Maximize => SC_MAXIMIZE,
+    CaptionButton::Close => SC_CLOSE,
+  };
+  let _ = window.post_message(Message::new(MessageId::SysCommand, WParam(command as usize), LParam(0)));
+}
+
+/// A window procedure with [`Decorations`] in front of it. The procedure gets the first say on
+/// `NcHitTest` only, so it can still claim parts of the title bar, e.g. for controls drawn in it.
+pub struct Decorated<P> {
+  pub decorations: Decorations,
+  pub procedure: P,
+}
+
+impl<P> Decorated<P> {
+  pub fn new(decorations: Decorations, procedure: P) -> Self {
+    Self { decorations, procedure }
+  }
+}
+
+impl<P: WindowProcedure> WindowProcedure for Decorated<P> {
+  fn on_message(&mut self, window: &Window, message: &Message) -> Option<LResult> {
+    match message {
+      Message::NcHitTest(_) => {
+        self.procedure.on_message(window, message).or_else(|| self.decorations.on_message(window, message))
+      },
+      _ => {
+        self.decorations.on_message(window, message).or_else(|| self.procedure.on_message(window, message))
+      },
+    }
+  }
+}
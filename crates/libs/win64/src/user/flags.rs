@@ -18,6 +18,8 @@ pub enum WindowPtrIndex {
   Id,
   UserData,
   WndProc,
+  Style,
+  ExStyle,
 }
 
 impl WindowPtrIndex {
@@ -30,6 +32,8 @@ impl WindowPtrIndex {
       WindowPtrIndex::Id => WindowsAndMessaging::GWL_ID,
       WindowPtrIndex::UserData => WindowsAndMessaging::GWL_USERDATA,
       WindowPtrIndex::WndProc => WindowsAndMessaging::GWL_WNDPROC,
+      WindowPtrIndex::Style => WindowsAndMessaging::GWL_STYLE,
+      WindowPtrIndex::ExStyle => WindowsAndMessaging::GWL_EXSTYLE,
     }
     #[cfg(target_pointer_width = "64")]
     match self {
@@ -38,6 +42,8 @@ impl WindowPtrIndex {
       WindowPtrIndex::Id => WindowsAndMessaging::GWLP_ID,
       WindowPtrIndex::UserData => WindowsAndMessaging::GWLP_USERDATA,
       WindowPtrIndex::WndProc => WindowsAndMessaging::GWLP_WNDPROC,
+      WindowPtrIndex::Style => WindowsAndMessaging::GWL_STYLE,
+      WindowPtrIndex::ExStyle => WindowsAndMessaging::GWL_EXSTYLE,
     }
   }
 }
@@ -1,31 +1,55 @@
+pub mod cursor;
+pub mod dwm;
+pub mod fullscreen;
+pub use fullscreen::Fullscreen;
+pub mod hierarchy;
+pub use hierarchy::Ancestor;
+pub mod hotkey;
+pub mod icon;
+pub mod layered;
 pub mod paint;
 pub use paint::*;
+pub mod placement;
+pub use placement::{
+  PlacementState,
+  WindowPlacement,
+};
+pub mod props;
+pub use props::PropKey;
+pub mod scroll;
+pub mod state;
+pub mod style;
+pub mod subclass;
 use {
   super::{
+    Icon,
     Instance,
     LResult,
     Message,
     Monitor,
     MonitorDefault,
     UserData,
+    UserDataGuard,
     WindowClass,
     WindowPtrIndex,
     WindowStyle,
     dpi_to_scale_factor,
     procedure::{
+      NestedProcedure,
       WindowProcedure,
       WindowState,
+      window_procedure,
     },
     styles::ExtendedWindowStyle,
   },
   crate::{
     Handle,
     Rect,
+    convert_error,
     declare_handle,
     get_last_error,
     last_error,
     reset_last_error,
-    win10_build_version,
   },
   dpi::{
     PhysicalPosition,
@@ -36,6 +60,7 @@ use {
   },
   std::{
     collections::HashMap,
+    rc::Rc,
     sync::{
       LazyLock,
       RwLock,
@@ -50,27 +75,20 @@ use {
   windows_sys::Win32::{
     Foundation::{
       self,
+      ERROR_NOT_SUPPORTED,
       LPARAM,
+      POINT,
+      RECT,
       WPARAM,
     },
     Graphics::{
-      Dwm::{
-        DWMSBT_AUTO,
-        DWMSBT_MAINWINDOW,
-        DWMSBT_NONE,
-        DWMSBT_TABBEDWINDOW,
-        DWMSBT_TRANSIENTWINDOW,
-        DWMWA_BORDER_COLOR,
-        DWMWA_CAPTION_COLOR,
-        DWMWA_SYSTEMBACKDROP_TYPE,
-        DWMWA_USE_IMMERSIVE_DARK_MODE,
-        DWMWINDOWATTRIBUTE,
-        DwmExtendFrameIntoClientArea,
-        DwmSetWindowAttribute,
-      },
+      Dwm::DwmExtendFrameIntoClientArea,
       Gdi::{
         self,
+        ClientToScreen,
+        MapWindowPoints,
         RedrawWindow,
+        ScreenToClient,
         UpdateWindow,
       },
     },
@@ -78,6 +96,7 @@ use {
     UI::{
       Controls::MARGINS,
       HiDpi::{
+        AdjustWindowRectExForDpi,
         EnableNonClientDpiScaling,
         GetDpiForWindow,
       },
@@ -87,6 +106,8 @@ use {
         CreateWindowExW,
         DefWindowProcW,
         DestroyWindow,
+        GCLP_WNDPROC,
+        GetClassLongPtrW,
         GetClientRect,
         GetWindowLongPtrW,
         GetWindowRect,
@@ -107,6 +128,24 @@ use {
     },
   },
 };
+pub use {
+  dwm::{
+    Backdrop,
+    Cloaked,
+    DwmColor,
+    DwmWindowAttribute,
+    Flip3DPolicy,
+    NcRenderingPolicy,
+    SystemBackdropType,
+    WindowCornerPreference,
+  },
+  state::{
+    FlashFlags,
+    allow_set_foreground_window,
+  },
+  style::StyleChange,
+  subclass::SubclassId,
+};
 
 declare_handle!(
   Window,
@@ -137,6 +176,8 @@ pub struct CreateStruct {
   pub ex_style: ExtendedWindowStyle,
   pub position: (Option<PixelUnit>, Option<PixelUnit>),
   pub size: (Option<PixelUnit>, Option<PixelUnit>),
+  /// The size of the client area. Takes precedence over `size` when set.
+  pub inner_size: Option<Size>,
   pub parent: Option<Window>,
   pub menu: Option<*mut ()>,
   pub instance: Option<Instance>,
@@ -155,14 +196,58 @@ impl CreateStruct {
     self.position.1.map(|p| p.to_physical(scale_factor).0).unwrap_or(CW_USEDEFAULT)
   }
 
+  /// The outer size that makes the client area exactly `inner_size`, or `None` if it isn't set. Uses the
+  /// DPI of the parent for child windows, whose position is relative to it, and otherwise the DPI of the
+  /// monitor the window will appear on.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-adjustwindowrectexfordpi"]
+  pub fn outer_size_for_inner(&self) -> Result<Option<PhysicalSize<i32>>> {
+    let Some(inner_size) = self.inner_size else {
+      return Ok(None);
+    };
+    let dpi = match (self.parent, self.position) {
+      (Some(parent), _) if self.style.contains(WindowStyle::Child) => parent.dpi(),
+      (_, (Some(_), Some(_))) => {
+        Monitor::from_point(PhysicalPosition::new(self.x(), self.y()), MonitorDefault::Nearest)
+          .unwrap_or_else(Monitor::primary)
+          .dpi()
+      },
+      // Windows without a position are placed on the primary monitor.
+      _ => Monitor::primary().dpi(),
+    };
+
+    let inner_size: PhysicalSize<i32> = inner_size.to_physical(dpi_to_scale_factor(dpi));
+    let mut rect = RECT { left: 0, top: 0, right: inner_size.width, bottom: inner_size.height };
+    reset_last_error();
+    let adjusted = unsafe {
+      AdjustWindowRectExForDpi(
+        &mut rect,
+        self.style.to_raw(),
+        self.menu.is_some().into(),
+        self.ex_style.to_raw(),
+        dpi,
+      )
+    };
+    match adjusted {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      _ => Ok(Some(PhysicalSize::new(rect.right - rect.left, rect.bottom - rect.top))),
+    }
+  }
+
   #[inline]
   pub fn width(&self) -> i32 {
+    if let Ok(Some(size)) = self.outer_size_for_inner() {
+      return size.width;
+    }
     let scale_factor = Monitor::primary().scale_factor();
     self.size.0.map(|p| p.to_physical(scale_factor).0).unwrap_or(CW_USEDEFAULT)
   }
 
   #[inline]
   pub fn height(&self) -> i32 {
+    if let Ok(Some(size)) = self.outer_size_for_inner() {
+      return size.height;
+    }
     let scale_factor = Monitor::primary().scale_factor();
     self.size.1.map(|p| p.to_physical(scale_factor).0).unwrap_or(CW_USEDEFAULT)
   }
@@ -180,6 +265,13 @@ pub fn create_window(create_struct: CreateStruct, wnd_proc: Box<dyn WindowProced
   // let mut new_style = desc.style;
   // new_style.remove(WindowStyle::Visible);
 
+  // Checked before anything is allocated, since `width` and `height` fall back to `size` on failure.
+  let outer_size = create_struct.outer_size_for_inner()?;
+  let (width, height) = match outer_size {
+    Some(size) => (size.width, size.height),
+    None => (create_struct.width(), create_struct.height()),
+  };
+
   let lp_param_ptr = Box::into_raw(Box::new(LpParam { create_struct, wnd_proc: Some(wnd_proc) }));
   let lp_param = unsafe { lp_param_ptr.as_ref() }.unwrap();
 
@@ -193,8 +285,8 @@ pub fn create_window(create_struct: CreateStruct, wnd_proc: Box<dyn WindowProced
       lp_param.create_struct.style.to_raw(),
       lp_param.create_struct.x(),
       lp_param.create_struct.y(),
-      lp_param.create_struct.width(),
-      lp_param.create_struct.height(),
+      width,
+      height,
       lp_param.create_struct.parent.unwrap_or_default().to_raw() as _,
       lp_param.create_struct.menu.unwrap_or_else(std::ptr::null_mut) as _,
       lp_param.create_struct.instance.unwrap_or_default().to_raw() as _,
@@ -235,6 +327,8 @@ pub struct WindowBuilder<WndClass, WndProc> {
   ex_style: ExtendedWindowStyle,
   position: (Option<PixelUnit>, Option<PixelUnit>),
   size: (Option<PixelUnit>, Option<PixelUnit>),
+  inner_size: Option<Size>,
+  icon: (Option<Icon>, Option<Icon>),
   parent: Option<Window>,
   menu: Option<*mut ()>,
   instance: Option<Instance>,
@@ -250,6 +344,8 @@ impl Default for WindowBuilder<NoClass, NoProc> {
       ex_style: ExtendedWindowStyle::default(),
       position: (None, None),
       size: (None, None),
+      inner_size: None,
+      icon: (None, None),
       parent: None,
       menu: None,
       instance: Some(Instance::get()),
@@ -267,6 +363,8 @@ impl<WndProc> WindowBuilder<NoClass, WndProc> {
       ex_style: self.ex_style,
       position: self.position,
       size: self.size,
+      inner_size: self.inner_size,
+      icon: self.icon,
       parent: self.parent,
       menu: self.menu,
       instance: self.instance,
@@ -284,6 +382,8 @@ impl<WndClass> WindowBuilder<WndClass, NoProc> {
       ex_style: self.ex_style,
       position: self.position,
       size: self.size,
+      inner_size: self.inner_size,
+      icon: self.icon,
       parent: self.parent,
       menu: self.menu,
       instance: self.instance,
@@ -351,6 +451,20 @@ impl<WndClass, WndProc> WindowBuilder<WndClass, WndProc> {
     self
   }
 
+  /// Sizes the window so its client area is exactly `size`. Logical sizes are scaled for the monitor the
+  /// window will appear on. Overrides [`WindowBuilder::with_size`].
+  pub fn with_inner_size(mut self, size: Option<impl Into<Size>>) -> WindowBuilder<WndClass, WndProc> {
+    self.inner_size = size.map(Into::into);
+    self
+  }
+
+  /// Sets the taskbar (`big`) and title bar (`small`) icons once the window is created, overriding those of
+  /// the window class. See [`Window::set_icon`].
+  pub fn with_icon(mut self, big: Option<Icon>, small: Option<Icon>) -> WindowBuilder<WndClass, WndProc> {
+    self.icon = (big, small);
+    self
+  }
+
   pub fn with_parent(mut self, parent: Option<Window>) -> WindowBuilder<WndClass, WndProc> {
     self.parent = parent;
     self
@@ -369,7 +483,7 @@ impl<WndClass, WndProc> WindowBuilder<WndClass, WndProc> {
 
 impl WindowBuilder<Class, Proc> {
   pub fn create(self) -> Result<Window> {
-    Window::new(
+    let window = Window::new(
       CreateStruct {
         class: self.class.0,
         name: self.name,
@@ -377,12 +491,18 @@ impl WindowBuilder<Class, Proc> {
         ex_style: self.ex_style,
         position: self.position,
         size: self.size,
+        inner_size: self.inner_size,
         parent: self.parent,
         menu: self.menu,
         instance: self.instance,
       },
       self.wnd_proc.0,
-    )
+    )?;
+    let (big, small) = self.icon;
+    if big.is_some() || small.is_some() {
+      window.set_icon(big, small);
+    }
+    Ok(window)
   }
 }
 
@@ -464,85 +584,11 @@ impl Window {
   }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum DwmWindowAttribute {
-  UseImmersiveDarkMode(bool),
-  SystemBackdropType(SystemBackdropType),
-  CaptionColor(u32),
-  BorderColor(u32),
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum SystemBackdropType {
-  Auto,
-  None,
-  MainWindow,
-  TransientWindow,
-  TabbedWindow,
-}
-
-impl SystemBackdropType {
-  pub const fn to_raw(&self) -> i32 {
-    match self {
-      Self::Auto => DWMSBT_AUTO,
-      Self::None => DWMSBT_NONE,
-      Self::MainWindow => DWMSBT_MAINWINDOW,
-      Self::TransientWindow => DWMSBT_TRANSIENTWINDOW,
-      Self::TabbedWindow => DWMSBT_TABBEDWINDOW,
-    }
-  }
-}
-
 impl Window {
   pub fn enable_non_client_dpi_scaling(&self) {
     unsafe { EnableNonClientDpiScaling(self.to_ptr()) };
   }
 
-  // WIP
-  pub fn dwm_set_window_attribute(&self, attribute: DwmWindowAttribute) {
-    match attribute {
-      DwmWindowAttribute::UseImmersiveDarkMode(enable) => self.use_immersive_dark_mode(enable),
-      DwmWindowAttribute::SystemBackdropType(backdrop_type) => {
-        self.system_backdrop_type(backdrop_type.to_raw())
-      },
-      DwmWindowAttribute::CaptionColor(color) => self.caption_color(color),
-      DwmWindowAttribute::BorderColor(color) => self.border_color(color),
-    }
-  }
-
-  fn border_color(&self, color: u32) {
-    unsafe {
-      DwmSetWindowAttribute(
-        self.to_ptr(),
-        DWMWA_CAPTION_COLOR as u32,
-        &raw const color as *const std::ffi::c_void,
-        std::mem::size_of::<DWMWINDOWATTRIBUTE>() as u32,
-      )
-    };
-  }
-
-  fn caption_color(&self, color: u32) {
-    unsafe {
-      DwmSetWindowAttribute(
-        self.to_ptr(),
-        DWMWA_BORDER_COLOR as u32,
-        &raw const color as *const std::ffi::c_void,
-        std::mem::size_of::<DWMWINDOWATTRIBUTE>() as u32,
-      )
-    };
-  }
-
-  fn system_backdrop_type(&self, backdrop_type: i32) {
-    unsafe {
-      DwmSetWindowAttribute(
-        self.to_ptr(),
-        DWMWA_SYSTEMBACKDROP_TYPE as u32,
-        &raw const backdrop_type as *const std::ffi::c_void,
-        std::mem::size_of::<DWMWINDOWATTRIBUTE>() as u32,
-      )
-    };
-  }
-
   pub fn extend_into_client_all(&self) {
     let margins = MARGINS { cxLeftWidth: -1, cxRightWidth: -1, cyTopHeight: -1, cyBottomHeight: -1 };
 
@@ -551,102 +597,6 @@ impl Window {
     }
   }
 
-  // Work in progress. Need to fix titlebar colors.
-  pub fn set_acrylic_background(&self, _color: u32) {
-    todo!()
-    // Reference: https://gist.github.com/xv/43bd4c944202a593ac8ec6daa299b471
-    // Keeping this internal for now while I figure out this API
-    // #[allow(unused)]
-    // #[repr(u32)]
-    // enum WindowCompositionAttribute {
-    //   Undefined = 0,
-    //   NcRenderingEnabled = 1,
-    //   NcRenderingPolicy = 2,
-    //   TransitionsForceDisabled = 3,
-    //   AllowNcPaint = 4,
-    //   CaptionButtonBounds = 5,
-    //   NonclientRtlLayout = 6,
-    //   ForceIconicRepresentation = 7,
-    //   ExtendedFrameBounds = 8,
-    //   HasIconicBitmap = 9,
-    //   ThemeAttributes = 10,
-    //   NcRenderingExiled = 11,
-    //   NcAdornmentInfo = 12,
-    //   ExcludedFromLivePreview = 13,
-    //   VideoOverlayActive = 14,
-    //   ForceActiveWindowAppearance = 15,
-    //   DisallowPeek = 16,
-    //   Cloak = 17,
-    //   Cloaked = 18,
-    //   AccentPolicy = 19,
-    //   FreezeRepresentation = 20,
-    //   EverUncloaked = 21,
-    //   VisualOwner = 22,
-    //   Holographic = 23,
-    //   ExcludedFromDda = 24,
-    //   PassiveUpdateMode = 25,
-    //   Last = 26,
-    // }
-
-    // #[allow(unused)]
-    // #[repr(u32)]
-    // enum AccentState {
-    //   Disabled = 0,
-    //   EnableGradient = 1,
-    //   EnableTransparentGradient = 2,
-    //   EnableBlurBehind = 3,
-    //   EnableAcrylicBlurBehind = 4, // RS4 1803.17063
-    //   EnableHostBackdrop = 5,      // RS5 1809
-    //   InvalidState = 6,
-    // }
-
-    // #[repr(C)]
-    // struct AccentPolicy {
-    //   accent_state: AccentState,
-    //   accent_flags: u32,
-    //   gradient_color: u32,
-    //   animation_id: u32,
-    // }
-
-    // #[repr(C)]
-    // struct WindowCompositionAttributeData {
-    //   attribute: WindowCompositionAttribute,
-    //   data: *mut std::ffi::c_void,
-    //   size: usize,
-    // }
-
-    // let user32 = unsafe { libloading::Library::new("user32.dll\0") }.unwrap();
-
-    // let set_window_composition_attribute: Symbol<
-    //   unsafe extern "system" fn(hwnd: HWND, data: *mut WindowCompositionAttributeData) -> i32,
-    // > = unsafe { user32.get(b"SetWindowCompositionAttribute") }.unwrap();
-
-    // let policy = AccentPolicy {
-    //   accent_state: AccentState::EnableAcrylicBlurBehind,
-    //   accent_flags: 2,
-    //   gradient_color: color,
-    //   animation_id: 0,
-    // };
-
-    // let mut data = WindowCompositionAttributeData {
-    //   attribute: WindowCompositionAttribute::AccentPolicy,
-    //   data: &raw const policy as *mut std::ffi::c_void,
-    //   size: std::mem::size_of::<AccentPolicy>(),
-    // };
-
-    // unsafe {
-    //   set_window_composition_attribute(self.to_ptr(), &mut data);
-    // }
-
-    // let titlebar_height = unsafe { GetSystemMetrics(SM_CYCAPTION) };
-    // let margins = MARGINS {
-    //   cxLeftWidth: 0,
-    //   cxRightWidth: 0,
-    //   cyTopHeight: titlebar_height,
-    //   cyBottomHeight: 0,
-    // };
-  }
-
   pub fn dpi(&self) -> u32 {
     match unsafe { GetDpiForWindow(self.to_ptr()) } {
       0 => Monitor::BASE_DPI,
@@ -662,24 +612,6 @@ impl Window {
     Monitor::from_window(*self, MonitorDefault::Nearest).unwrap()
   }
 
-  fn use_immersive_dark_mode(&self, enable: bool) {
-    // https://learn.microsoft.com/en-us/windows/apps/desktop/modernize/ui/apply-windows-themes
-    let value = enable as windows_sys::core::BOOL;
-    let Some(version) = win10_build_version() else {
-      return;
-    };
-    // May 2020 Update https://stackoverflow.com/a/70693198/17004103
-    let dw_attribute: u32 = if version < 19041 { 19 } else { DWMWA_USE_IMMERSIVE_DARK_MODE as _ };
-    unsafe {
-      DwmSetWindowAttribute(
-        self.to_ptr(),
-        dw_attribute,
-        (&raw const value).cast(),
-        std::mem::size_of::<DWMWINDOWATTRIBUTE>() as u32,
-      )
-    };
-  }
-
   pub fn update(&self) -> Result<()> {
     reset_last_error();
     match unsafe { UpdateWindow(self.to_ptr()) } {
@@ -737,10 +669,10 @@ impl Window {
 
   pub fn destroy(&self) -> Result<()> {
     if self.is_window()
-      && let Some(data) = self.user_data()
-      && let WindowState::Running = data.state
+      && let Some(data) = self.owned_user_data()
+      && let WindowState::Running = data.state.get()
     {
-      data.state = WindowState::Destroying;
+      data.state.set(WindowState::Destroying);
       reset_last_error();
       return match unsafe { DestroyWindow(self.to_ptr()) } {
         0 => last_error(),
@@ -750,6 +682,19 @@ impl Window {
     Ok(())
   }
 
+  /// Has `procedure` handle the messages sent to the window while its [`WindowProcedure`] is busy, or stops
+  /// for `None`. See [`NestedProcedure`].
+  ///
+  /// Only windows created by this crate have a window procedure to be busy, and only their own thread can
+  /// reach it.
+  pub fn set_nested_procedure(&self, procedure: Option<Rc<dyn NestedProcedure>>) -> Result<()> {
+    let Some(data) = self.owned_user_data() else {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    };
+    data.proc.set_nested(procedure);
+    Ok(())
+  }
+
   pub fn get_platform_thread_id(&self) -> Option<u32> {
     let id = unsafe { GetWindowThreadProcessId(self.to_ptr(), std::ptr::null_mut()) };
     match id {
@@ -863,6 +808,59 @@ impl Window {
     last_error()
   }
 
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-clienttoscreen"]
+  pub fn client_to_screen(
+    &self,
+    position: impl Into<PhysicalPosition<i32>>,
+  ) -> Result<PhysicalPosition<i32>> {
+    let position = position.into();
+    let mut point = POINT { x: position.x, y: position.y };
+    reset_last_error();
+    match unsafe { ClientToScreen(self.to_ptr(), &mut point) } {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      _ => Ok(PhysicalPosition::new(point.x, point.y)),
+    }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-screentoclient"]
+  pub fn screen_to_client(
+    &self,
+    position: impl Into<PhysicalPosition<i32>>,
+  ) -> Result<PhysicalPosition<i32>> {
+    let position = position.into();
+    let mut point = POINT { x: position.x, y: position.y };
+    reset_last_error();
+    match unsafe { ScreenToClient(self.to_ptr(), &mut point) } {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      _ => Ok(PhysicalPosition::new(point.x, point.y)),
+    }
+  }
+
+  /// Converts points from this window's client coordinates to those of `to`, or to screen coordinates for
+  /// `None`.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-mapwindowpoints"]
+  pub fn map_points(
+    &self,
+    to: Option<Window>,
+    positions: &[PhysicalPosition<i32>],
+  ) -> Result<Vec<PhysicalPosition<i32>>> {
+    let mut points: Vec<POINT> =
+      positions.iter().map(|position| POINT { x: position.x, y: position.y }).collect();
+    reset_last_error();
+    // Zero is also a valid result when the windows line up, so only the last error tells them apart.
+    unsafe {
+      MapWindowPoints(
+        self.to_ptr(),
+        to.map_or(std::ptr::null_mut(), |to| to.to_ptr()),
+        points.as_mut_ptr(),
+        points.len() as u32,
+      )
+    };
+    last_error()?;
+    Ok(points.into_iter().map(|point| PhysicalPosition::new(point.x, point.y)).collect())
+  }
+
   pub(crate) fn get_window_ptr(&self, index: WindowPtrIndex) -> isize {
     unsafe { GetWindowLongPtrW(self.to_ptr(), index.to_raw()) as _ }
   }
@@ -882,9 +880,24 @@ impl Window {
     }
   }
 
-  #[allow(clippy::mut_from_ref)] // This is fine because self is just a handle.
-  #[inline]
-  pub(crate) fn user_data(&self) -> Option<&mut UserData> {
-    unsafe { (self.get_window_ptr(WindowPtrIndex::UserData) as *mut UserData).as_mut() }
+  /// The crate's data for a window created by it, if this thread owns the window.
+  ///
+  /// Other windows use their user data however they like, so it is only read once the window's class is
+  /// known to use [`window_procedure`]. The class is checked rather than the window, since subclassing
+  /// replaces the window's own procedure. The data isn't thread-safe, so other threads never see it.
+  ///
+  /// The guard keeps the data alive if the window is destroyed while it is held, e.g. by a message sent to
+  /// the window meanwhile, and is only ever shared, since the window procedure can be re-entered.
+  pub(crate) fn owned_user_data(&self) -> Option<UserDataGuard> {
+    if !self.is_current_thread()
+      || unsafe { GetClassLongPtrW(self.to_ptr(), GCLP_WNDPROC) } != window_procedure as *const () as usize
+    {
+      return None;
+    }
+    let data = self.get_window_ptr(WindowPtrIndex::UserData) as *mut UserData;
+    match data.is_null() {
+      true => None,
+      false => Some(unsafe { UserDataGuard::enter(data) }),
+    }
   }
 }
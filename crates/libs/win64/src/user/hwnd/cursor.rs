@@ -0,0 +1,191 @@
+use {
+  super::{
+    Window,
+    state::window_from_ptr,
+  },
+  crate::{
+    Rect,
+    convert_error,
+    high_word,
+    low_word,
+    user::{
+      Cursor,
+      LResult,
+      Message,
+      SetCursorMessage,
+      clip_cursor,
+      cursor_position,
+      dpi_to_scale_factor,
+      set_cursor,
+      set_cursor_position,
+    },
+  },
+  dpi::{
+    PhysicalPosition,
+    Position,
+  },
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::{
+      ERROR_NOT_SUPPORTED,
+      POINT,
+    },
+    UI::WindowsAndMessaging::{
+      self,
+      WindowFromPoint,
+    },
+  },
+};
+
+/// The cursor settings of a window created by this crate.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CursorState {
+  cursor: Option<Cursor>,
+  confined: bool,
+}
+
+impl SetCursorMessage {
+  /// The window the cursor is over, which may be a child of the one receiving the message.
+  pub fn window(&self) -> Option<Window> {
+    window_from_ptr(self.w.0 as _)
+  }
+
+  /// Where the cursor is over the window, as returned by `NcHitTest`, e.g. `HTCLIENT`.
+  pub fn hit_test(&self) -> u16 {
+    low_word(self.l.0 as u32)
+  }
+
+  /// The id of the mouse message that triggered this one, or zero when the window is entering menu mode.
+  pub fn mouse_message(&self) -> u16 {
+    high_word(self.l.0 as u32)
+  }
+}
+
+impl Window {
+  pub fn cursor(&self) -> Option<Cursor> {
+    self.cursor_state()?.cursor
+  }
+
+  /// Shows `cursor` whenever the cursor is over the client area, or lets the window class decide for
+  /// `None`. The cursor has to outlive the window, or at least its use here.
+  ///
+  /// The library answers `SetCursor` messages with it unless the window procedure handles them itself.
+  /// Only windows created by this crate have somewhere to keep the cursor, and only their own thread can
+  /// reach it.
+  pub fn set_cursor(&self, cursor: Option<Cursor>) -> Result<()> {
+    self.update_cursor_state(|state| CursorState { cursor, ..state })?;
+
+    // Otherwise the change only shows once the mouse moves.
+    if let Some(cursor) = cursor
+      && self.is_under_cursor()
+    {
+      set_cursor(Some(cursor));
+    }
+    Ok(())
+  }
+
+  pub fn is_cursor_confined(&self) -> bool {
+    self.cursor_state().is_some_and(|state| state.confined)
+  }
+
+  /// Keeps the cursor inside the client area while the window is active. The confinement is let go while
+  /// the window is inactive, and follows the client area as the window moves and resizes.
+  ///
+  /// Only windows created by this crate can confine the cursor, from their own thread, since the setting
+  /// is kept in the window's user data.
+  pub fn confine_cursor(&self, confine: bool) -> Result<()> {
+    self.update_cursor_state(|state| CursorState { confined: confine, ..state })?;
+    match confine {
+      true if Window::get_active_window() == Some(*self) => self.clip_to_client(),
+      true => Ok(()),
+      false => clip_cursor(None),
+    }
+  }
+
+  /// The cursor's position in client coordinates.
+  pub fn cursor_position(&self) -> Result<PhysicalPosition<i32>> {
+    self.screen_to_client(cursor_position()?)
+  }
+
+  /// Moves the cursor to a position in client coordinates. Logical positions are scaled by the window's
+  /// DPI.
+  pub fn set_cursor_position(&self, position: impl Into<Position>) -> Result<()> {
+    let position = position.into().to_physical(dpi_to_scale_factor(self.dpi()));
+    set_cursor_position(self.client_to_screen(position)?)
+  }
+
+  /// Answers `SetCursor` messages the window procedure left alone with the window's cursor, if it has one.
+  pub(crate) fn apply_cursor(&self, message: &Message) -> Option<LResult> {
+    let Message::SetCursor(set_cursor_message) = message else {
+      return None;
+    };
+    let cursor = self.cursor_state()?.cursor?;
+    match set_cursor_message.window() == Some(*self)
+      && set_cursor_message.hit_test() as u32 == WindowsAndMessaging::HTCLIENT
+    {
+      true => {
+        set_cursor(Some(cursor));
+        Some(LResult::TRUE)
+      },
+      false => None,
+    }
+  }
+
+  /// Re-applies or lets go of the cursor confinement as the window is activated, moved and destroyed.
+  pub(crate) fn update_cursor_clip(&self, message: &Message) {
+    if !self.is_cursor_confined() {
+      return;
+    }
+    let _ = match message {
+      Message::Activate(activate) => match low_word(activate.w.0 as u32) as u32 {
+        WindowsAndMessaging::WA_INACTIVE => clip_cursor(None),
+        _ => self.clip_to_client(),
+      },
+      Message::Move(_) | Message::Size(_) | Message::WindowPosChanged(_)
+        if Window::get_active_window() == Some(*self) =>
+      {
+        self.clip_to_client()
+      },
+      Message::Destroy => clip_cursor(None),
+      _ => Ok(()),
+    };
+  }
+
+  fn clip_to_client(&self) -> Result<()> {
+    let client = self.get_client_rect()?;
+    let top_left = self.client_to_screen(PhysicalPosition::new(client.left, client.top))?;
+    let bottom_right = self.client_to_screen(PhysicalPosition::new(client.right, client.bottom))?;
+    clip_cursor(Some(Rect {
+      left: top_left.x,
+      top: top_left.y,
+      right: bottom_right.x,
+      bottom: bottom_right.y,
+    }))
+  }
+
+  fn is_under_cursor(&self) -> bool {
+    let Ok(position) = cursor_position() else {
+      return false;
+    };
+    let hwnd = unsafe { WindowFromPoint(POINT { x: position.x, y: position.y }) };
+    window_from_ptr(hwnd) == Some(*self)
+      && self.screen_to_client(position).is_ok_and(|position| {
+        self.get_client_rect().is_ok_and(|client| {
+          (client.left..client.right).contains(&position.x)
+            && (client.top..client.bottom).contains(&position.y)
+        })
+      })
+  }
+
+  fn cursor_state(&self) -> Option<CursorState> {
+    self.owned_user_data().map(|data| data.cursor.get())
+  }
+
+  fn update_cursor_state(&self, update: impl FnOnce(CursorState) -> CursorState) -> Result<()> {
+    let Some(data) = self.owned_user_data() else {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    };
+    data.cursor.set(update(data.cursor.get()));
+    Ok(())
+  }
+}
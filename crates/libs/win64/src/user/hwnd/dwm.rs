@@ -0,0 +1,420 @@
+use {
+  super::Window,
+  crate::{
+    HResult,
+    Handle,
+    Rect,
+    convert_error,
+    last_error,
+    reset_last_error,
+    win10_build_version,
+  },
+  bitflags::bitflags,
+  libloading::Library,
+  rgb::{
+    RGB8,
+    RGBA8,
+  },
+  std::{
+    ffi::c_void,
+    sync::LazyLock,
+  },
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::{
+      ERROR_NOT_SUPPORTED,
+      HWND,
+      RECT,
+    },
+    Graphics::Dwm::{
+      self,
+      DWM_SYSTEMBACKDROP_TYPE,
+      DWM_WINDOW_CORNER_PREFERENCE,
+      DWMFLIP3DWINDOWPOLICY,
+      DWMNCRENDERINGPOLICY,
+      DWMWINDOWATTRIBUTE,
+      DwmGetWindowAttribute,
+      DwmSetWindowAttribute,
+    },
+  },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DwmWindowAttribute {
+  UseImmersiveDarkMode(bool),
+  SystemBackdropType(SystemBackdropType),
+  CaptionColor(DwmColor),
+  BorderColor(DwmColor),
+  /// The color of the title bar text.
+  TextColor(DwmColor),
+  WindowCornerPreference(WindowCornerPreference),
+  /// Hides the window from the user while it keeps being composed, e.g. to draw it once before showing
+  /// it without a white flash.
+  Cloak(bool),
+  /// Turns off the animations when the window is shown, hidden, minimized and restored.
+  TransitionsForceDisabled(bool),
+  NcRenderingPolicy(NcRenderingPolicy),
+  /// Keeps the window visible when the user peeks at the desktop or at another window from the taskbar.
+  ExcludedFromPeek(bool),
+  /// Keeps the taskbar from peeking at this window when the user hovers over its thumbnail.
+  DisallowPeek(bool),
+  Flip3DPolicy(Flip3DPolicy),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SystemBackdropType {
+  Auto,
+  None,
+  MainWindow,
+  TransientWindow,
+  TabbedWindow,
+}
+
+impl SystemBackdropType {
+  pub const fn to_raw(&self) -> DWM_SYSTEMBACKDROP_TYPE {
+    match self {
+      Self::Auto => Dwm::DWMSBT_AUTO,
+      Self::None => Dwm::DWMSBT_NONE,
+      Self::MainWindow => Dwm::DWMSBT_MAINWINDOW,
+      Self::TransientWindow => Dwm::DWMSBT_TRANSIENTWINDOW,
+      Self::TabbedWindow => Dwm::DWMSBT_TABBEDWINDOW,
+    }
+  }
+}
+
+/// A color for the caption, border or title bar text. Windows 11 only.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DwmColor {
+  /// The color Windows would use anyway.
+  #[default]
+  Default,
+  /// No border at all. Only for [`DwmWindowAttribute::BorderColor`].
+  None,
+  Rgb(RGB8),
+}
+
+impl DwmColor {
+  pub const fn to_raw(self) -> u32 {
+    match self {
+      Self::Default => Dwm::DWMWA_COLOR_DEFAULT,
+      Self::None => Dwm::DWMWA_COLOR_NONE,
+      Self::Rgb(RGB8 { r, g, b }) => ((b as u32) << 16) | ((g as u32) << 8) | r as u32,
+    }
+  }
+}
+
+/// How the corners of a top-level window are rounded. Windows 11 only.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WindowCornerPreference {
+  #[default]
+  Default,
+  DoNotRound,
+  Round,
+  RoundSmall,
+}
+
+impl WindowCornerPreference {
+  pub const fn to_raw(self) -> DWM_WINDOW_CORNER_PREFERENCE {
+    match self {
+      Self::Default => Dwm::DWMWCP_DEFAULT,
+      Self::DoNotRound => Dwm::DWMWCP_DONOTROUND,
+      Self::Round => Dwm::DWMWCP_ROUND,
+      Self::RoundSmall => Dwm::DWMWCP_ROUNDSMALL,
+    }
+  }
+}
+
+/// Whether the DWM draws the non-client area.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NcRenderingPolicy {
+  #[default]
+  UseWindowStyle,
+  Disabled,
+  Enabled,
+}
+
+impl NcRenderingPolicy {
+  pub const fn to_raw(self) -> DWMNCRENDERINGPOLICY {
+    match self {
+      Self::UseWindowStyle => Dwm::DWMNCRP_USEWINDOWSTYLE,
+      Self::Disabled => Dwm::DWMNCRP_DISABLED,
+      Self::Enabled => Dwm::DWMNCRP_ENABLED,
+    }
+  }
+}
+
+/// Where the window goes during Flip3D, which Windows 7 and earlier showed for Win+Tab.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Flip3DPolicy {
+  #[default]
+  Default,
+  ExcludeBelow,
+  ExcludeAbove,
+}
+
+impl Flip3DPolicy {
+  pub const fn to_raw(self) -> DWMFLIP3DWINDOWPOLICY {
+    match self {
+      Self::Default => Dwm::DWMFLIP3D_DEFAULT,
+      Self::ExcludeBelow => Dwm::DWMFLIP3D_EXCLUDEBELOW,
+      Self::ExcludeAbove => Dwm::DWMFLIP3D_EXCLUDEABOVE,
+    }
+  }
+}
+
+bitflags! {
+  /// Who is hiding a cloaked window.
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  pub struct Cloaked: u32 {
+    /// The app, with [`DwmWindowAttribute::Cloak`].
+    const App = Dwm::DWM_CLOAKED_APP;
+    /// The shell, e.g. because the window is on another virtual desktop.
+    const Shell = Dwm::DWM_CLOAKED_SHELL;
+    /// The window's owner is cloaked.
+    const Inherited = Dwm::DWM_CLOAKED_INHERITED;
+  }
+}
+
+impl Cloaked {
+  #[inline]
+  pub const fn to_raw(self) -> u32 {
+    self.bits()
+  }
+}
+
+impl Default for Cloaked {
+  fn default() -> Self {
+    Self::empty()
+  }
+}
+
+/// A see-through material behind the window. See [`Window::set_backdrop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Backdrop {
+  /// Turns off whichever backdrop was set before.
+  None,
+  /// A plain blur of whatever is behind the window. Windows 10 and later.
+  Blur,
+  /// A blur with noise and a tint, as in the Start menu. Windows 10 1803 and later. The tint is only
+  /// applied before Windows 11 22H2, which draws its own.
+  Acrylic(RGBA8),
+  /// A tint taken from the desktop wallpaper, for main windows. Windows 11 and later.
+  Mica,
+  /// A stronger Mica, for windows with tabs in the title bar. Windows 11 22H2 and later.
+  MicaAlt,
+}
+
+/// The first builds with each backdrop, after which it is set differently.
+const ACRYLIC_BUILD: u32 = 17063;
+const WINDOWS_11_BUILD: u32 = 22000;
+const SYSTEM_BACKDROP_BUILD: u32 = 22621;
+
+/// Undocumented, and only read by Windows 11 builds before `SYSTEM_BACKDROP_BUILD`.
+const DWMWA_MICA_EFFECT: DWMWINDOWATTRIBUTE = 1029;
+
+// Undocumented, see https://gist.github.com/xv/43bd4c944202a593ac8ec6daa299b471
+const WCA_ACCENT_POLICY: u32 = 19;
+
+#[allow(unused)]
+#[repr(u32)]
+#[derive(Clone, Copy)]
+enum AccentState {
+  Disabled = 0,
+  EnableGradient = 1,
+  EnableTransparentGradient = 2,
+  EnableBlurBehind = 3,
+  EnableAcrylicBlurBehind = 4,
+  EnableHostBackdrop = 5,
+}
+
+#[repr(C)]
+struct AccentPolicy {
+  accent_state: AccentState,
+  accent_flags: u32,
+  /// `0xAABBGGRR`
+  gradient_color: u32,
+  animation_id: u32,
+}
+
+#[repr(C)]
+struct WindowCompositionAttributeData {
+  attribute: u32,
+  data: *mut c_void,
+  size: usize,
+}
+
+type SetWindowCompositionAttribute =
+  unsafe extern "system" fn(HWND, *mut WindowCompositionAttributeData) -> i32;
+
+impl Window {
+  /// Attributes that need a newer version of Windows than the one running fail with `E_INVALIDARG`.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/dwmapi/nf-dwmapi-dwmsetwindowattribute"]
+  pub fn dwm_set_window_attribute(&self, attribute: DwmWindowAttribute) -> Result<()> {
+    let bool = |value: bool| value as windows_sys::core::BOOL;
+    match attribute {
+      DwmWindowAttribute::UseImmersiveDarkMode(enable) => self.use_immersive_dark_mode(enable),
+      DwmWindowAttribute::SystemBackdropType(backdrop_type) => {
+        self.set_dwm_value(Dwm::DWMWA_SYSTEMBACKDROP_TYPE, backdrop_type.to_raw())
+      },
+      DwmWindowAttribute::CaptionColor(color) => self.set_dwm_value(Dwm::DWMWA_CAPTION_COLOR, color.to_raw()),
+      DwmWindowAttribute::BorderColor(color) => self.set_dwm_value(Dwm::DWMWA_BORDER_COLOR, color.to_raw()),
+      DwmWindowAttribute::TextColor(color) => self.set_dwm_value(Dwm::DWMWA_TEXT_COLOR, color.to_raw()),
+      DwmWindowAttribute::WindowCornerPreference(preference) => {
+        self.set_dwm_value(Dwm::DWMWA_WINDOW_CORNER_PREFERENCE, preference.to_raw())
+      },
+      DwmWindowAttribute::Cloak(cloak) => self.set_dwm_value(Dwm::DWMWA_CLOAK, bool(cloak)),
+      DwmWindowAttribute::TransitionsForceDisabled(disable) => {
+        self.set_dwm_value(Dwm::DWMWA_TRANSITIONS_FORCEDISABLED, bool(disable))
+      },
+      DwmWindowAttribute::NcRenderingPolicy(policy) => {
+        self.set_dwm_value(Dwm::DWMWA_NCRENDERING_POLICY, policy.to_raw())
+      },
+      DwmWindowAttribute::ExcludedFromPeek(exclude) => {
+        self.set_dwm_value(Dwm::DWMWA_EXCLUDED_FROM_PEEK, bool(exclude))
+      },
+      DwmWindowAttribute::DisallowPeek(disallow) => {
+        self.set_dwm_value(Dwm::DWMWA_DISALLOW_PEEK, bool(disallow))
+      },
+      DwmWindowAttribute::Flip3DPolicy(policy) => {
+        self.set_dwm_value(Dwm::DWMWA_FLIP3D_POLICY, policy.to_raw())
+      },
+    }
+  }
+
+  /// Reads a DWM attribute into a `T`.
+  ///
+  /// # Safety
+  /// `T` has to have the size and layout of the value Windows writes for `attribute`, e.g. `RECT` for
+  /// `DWMWA_EXTENDED_FRAME_BOUNDS`. Prefer the typed getters, such as [`Window::dwm_cloaked`].
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/dwmapi/nf-dwmapi-dwmgetwindowattribute"]
+  pub unsafe fn dwm_get_window_attribute<T: Default>(&self, attribute: DWMWINDOWATTRIBUTE) -> Result<T> {
+    let mut value = T::default();
+    HResult(unsafe {
+      DwmGetWindowAttribute(
+        self.to_ptr(),
+        attribute as u32,
+        (&raw mut value).cast::<c_void>(),
+        size_of::<T>() as u32,
+      )
+    })
+    .ok()?;
+    Ok(value)
+  }
+
+  /// The bounds of the window as drawn, in screen coordinates. Unlike [`Window::get_window_rect`], these
+  /// leave out the invisible resize borders and aren't scaled for DPI-unaware windows.
+  pub fn dwm_extended_frame_bounds(&self) -> Result<Rect> {
+    unsafe { self.dwm_get_window_attribute::<RECT>(Dwm::DWMWA_EXTENDED_FRAME_BOUNDS) }.map(Rect::from)
+  }
+
+  /// Why the window is cloaked, or an empty set if it isn't.
+  pub fn dwm_cloaked(&self) -> Result<Cloaked> {
+    unsafe { self.dwm_get_window_attribute::<u32>(Dwm::DWMWA_CLOAKED) }.map(Cloaked::from_bits_retain)
+  }
+
+  /// The width of the visible border around the window, in pixels. Windows 11 only.
+  pub fn dwm_visible_frame_border_thickness(&self) -> Result<u32> {
+    unsafe { self.dwm_get_window_attribute::<u32>(Dwm::DWMWA_VISIBLE_FRAME_BORDER_THICKNESS) }
+  }
+
+  fn set_dwm_value<T>(&self, attribute: DWMWINDOWATTRIBUTE, value: T) -> Result<()> {
+    HResult(unsafe {
+      DwmSetWindowAttribute(
+        self.to_ptr(),
+        attribute as u32,
+        (&raw const value).cast::<c_void>(),
+        size_of::<T>() as u32,
+      )
+    })
+    .ok()
+  }
+
+  fn use_immersive_dark_mode(&self, enable: bool) -> Result<()> {
+    // https://learn.microsoft.com/en-us/windows/apps/desktop/modernize/ui/apply-windows-themes
+    let Some(version) = win10_build_version() else {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    };
+    // May 2020 Update https://stackoverflow.com/a/70693198/17004103
+    let attribute = if version < 19041 { 19 } else { Dwm::DWMWA_USE_IMMERSIVE_DARK_MODE };
+    self.set_dwm_value(attribute, enable as windows_sys::core::BOOL)
+  }
+
+  /// Puts `backdrop` behind the window. The window has to leave its client area transparent, e.g. by
+  /// painting it black, for the backdrop to show through.
+  ///
+  /// Which API is used depends on [`win10_build_version`]: the documented `DWMWA_SYSTEMBACKDROP_TYPE`
+  /// from Windows 11 22H2 on, and the undocumented accent policy of `SetWindowCompositionAttribute` on
+  /// earlier builds. Backdrops the running build doesn't have, and every backdrop before Windows 10,
+  /// fail with `ERROR_NOT_SUPPORTED`.
+  pub fn set_backdrop(&self, backdrop: Backdrop) -> Result<()> {
+    let unsupported = || Err(convert_error(ERROR_NOT_SUPPORTED));
+    let Some(build) = win10_build_version() else {
+      return unsupported();
+    };
+
+    let system_backdrop = build >= SYSTEM_BACKDROP_BUILD;
+    match backdrop {
+      Backdrop::None => {
+        if system_backdrop {
+          self.dwm_set_window_attribute(DwmWindowAttribute::SystemBackdropType(SystemBackdropType::None))?;
+        } else if build >= WINDOWS_11_BUILD {
+          self.set_dwm_value(DWMWA_MICA_EFFECT, false as windows_sys::core::BOOL)?;
+        }
+        self.set_accent(AccentState::Disabled, 0)
+      },
+      Backdrop::Blur => self.set_accent(AccentState::EnableBlurBehind, 0),
+      Backdrop::Acrylic(_) if system_backdrop => {
+        self.set_system_backdrop(SystemBackdropType::TransientWindow)
+      },
+      Backdrop::Acrylic(RGBA8 { r, g, b, a }) if build >= ACRYLIC_BUILD => {
+        let tint = ((a as u32) << 24) | ((b as u32) << 16) | ((g as u32) << 8) | r as u32;
+        self.set_accent(AccentState::EnableAcrylicBlurBehind, tint)
+      },
+      Backdrop::Mica if system_backdrop => self.set_system_backdrop(SystemBackdropType::MainWindow),
+      Backdrop::Mica if build >= WINDOWS_11_BUILD => {
+        self.extend_into_client_all();
+        self.set_dwm_value(DWMWA_MICA_EFFECT, true as windows_sys::core::BOOL)
+      },
+      Backdrop::MicaAlt if system_backdrop => self.set_system_backdrop(SystemBackdropType::TabbedWindow),
+      Backdrop::Acrylic(_) | Backdrop::Mica | Backdrop::MicaAlt => unsupported(),
+    }
+  }
+
+  /// Shorthand for [`Window::set_backdrop`] with [`Backdrop::Acrylic`].
+  pub fn set_acrylic_background(&self, tint: RGBA8) -> Result<()> {
+    self.set_backdrop(Backdrop::Acrylic(tint))
+  }
+
+  fn set_system_backdrop(&self, backdrop_type: SystemBackdropType) -> Result<()> {
+    // The backdrop only fills the frame, so the frame has to cover the client area too.
+    self.extend_into_client_all();
+    self.dwm_set_window_attribute(DwmWindowAttribute::SystemBackdropType(backdrop_type))
+  }
+
+  fn set_accent(&self, accent_state: AccentState, gradient_color: u32) -> Result<()> {
+    static USER32: LazyLock<Option<Library>> = LazyLock::new(|| unsafe { Library::new("user32.dll\0") }.ok());
+
+    let Some(set_window_composition_attribute) = USER32.as_ref().and_then(|user32| {
+      unsafe { user32.get::<SetWindowCompositionAttribute>(b"SetWindowCompositionAttribute") }.ok()
+    }) else {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    };
+
+    // Draws the backdrop under all four borders, not just the client area.
+    const ACCENT_FLAGS: u32 = 2;
+    let mut policy =
+      AccentPolicy { accent_state, accent_flags: ACCENT_FLAGS, gradient_color, animation_id: 0 };
+    let mut data = WindowCompositionAttributeData {
+      attribute: WCA_ACCENT_POLICY,
+      data: (&raw mut policy).cast(),
+      size: size_of::<AccentPolicy>(),
+    };
+    reset_last_error();
+    match unsafe { set_window_composition_attribute(self.to_ptr(), &mut data) } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+}
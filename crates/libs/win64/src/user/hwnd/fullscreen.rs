@@ -0,0 +1,246 @@
+use {
+  super::{
+    Window,
+    placement::WindowPlacement,
+  },
+  crate::{
+    Handle,
+    convert_error,
+    last_error,
+    reset_last_error,
+    user::{
+      ExtendedWindowStyle,
+      Message,
+      Monitor,
+      VideoMode,
+      WindowPtrIndex,
+      WindowStyle,
+    },
+  },
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::{
+      ERROR_BAD_ARGUMENTS,
+      ERROR_CANTWRITE,
+      ERROR_GEN_FAILURE,
+      ERROR_INVALID_FLAGS,
+      ERROR_INVALID_PARAMETER,
+      ERROR_NOT_SUPPORTED,
+      ERROR_SUCCESS_REBOOT_REQUIRED,
+    },
+    Graphics::Gdi::{
+      self,
+      CDS_FULLSCREEN,
+      ChangeDisplaySettingsExW,
+    },
+    UI::WindowsAndMessaging::{
+      self,
+      HWND_TOP,
+      SW_MINIMIZE,
+      SW_RESTORE,
+      SetWindowPos,
+      ShowWindow,
+    },
+  },
+};
+
+/// How a window covers a monitor. See [`Window::set_fullscreen`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fullscreen {
+  /// Covers the monitor with a borderless window, leaving its display mode alone.
+  Borderless(Monitor),
+  /// Switches the monitor to one of its modes from [`Monitor::video_modes`] and covers it.
+  Exclusive(Monitor, VideoMode),
+}
+
+impl Fullscreen {
+  pub const fn monitor(&self) -> Monitor {
+    match self {
+      Fullscreen::Borderless(monitor) | Fullscreen::Exclusive(monitor, _) => *monitor,
+    }
+  }
+}
+
+/// What a window looked like before it went fullscreen, so it can be put back.
+#[derive(Clone, Copy)]
+pub(crate) struct FullscreenState {
+  fullscreen: Fullscreen,
+  style: WindowStyle,
+  ex_style: ExtendedWindowStyle,
+  placement: WindowPlacement,
+}
+
+/// Changes the display mode of `monitor`, or resets it to the mode in the registry for `None`.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-changedisplaysettingsexw"]
+fn change_display_mode(monitor: Monitor, mode: Option<&VideoMode>) -> Result<()> {
+  let device_name: Vec<u16> = monitor.device_name()?.encode_utf16().chain(Some(0)).collect();
+  let mode = mode.map(VideoMode::to_raw);
+  let (mode, flags) = match &mode {
+    Some(mode) => (mode as *const _, CDS_FULLSCREEN),
+    None => (std::ptr::null(), 0),
+  };
+  let error = match unsafe {
+    ChangeDisplaySettingsExW(device_name.as_ptr(), mode, std::ptr::null_mut(), flags, std::ptr::null())
+  } {
+    Gdi::DISP_CHANGE_SUCCESSFUL => return Ok(()),
+    // The mode isn't one the monitor supports.
+    Gdi::DISP_CHANGE_BADMODE => ERROR_INVALID_PARAMETER,
+    Gdi::DISP_CHANGE_BADPARAM => ERROR_BAD_ARGUMENTS,
+    Gdi::DISP_CHANGE_BADFLAGS => ERROR_INVALID_FLAGS,
+    // The system is DualView capable, which doesn't allow changing modes this way.
+    Gdi::DISP_CHANGE_BADDUALVIEW => ERROR_NOT_SUPPORTED,
+    // The settings couldn't be written to the registry.
+    Gdi::DISP_CHANGE_NOTUPDATED => ERROR_CANTWRITE,
+    // The computer has to be restarted for the mode to take effect.
+    Gdi::DISP_CHANGE_RESTART => ERROR_SUCCESS_REBOOT_REQUIRED,
+    // The display driver failed the mode, or any code newer than these.
+    _ => ERROR_GEN_FAILURE,
+  };
+  Err(convert_error(error))
+}
+
+impl Window {
+  pub fn fullscreen(&self) -> Option<Fullscreen> {
+    self.owned_user_data()?.fullscreen.get().map(|state| state.fullscreen)
+  }
+
+  /// Makes the window cover a monitor, or puts it back the way it was for `None`.
+  ///
+  /// The style, position and maximized state from before the window first went fullscreen are kept
+  /// until it leaves, so switching between monitors or modes while fullscreen doesn't lose them. For
+  /// [`Fullscreen::Exclusive`], the previous display mode is put back when the window leaves
+  /// fullscreen, when the app loses focus (which also minimizes the window) and when it is destroyed.
+  ///
+  /// Only windows created by this crate can go fullscreen, from their own thread, since the state to
+  /// restore is kept in the window's user data.
+  pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) -> Result<()> {
+    // The state is looked up again after every call that can send messages rather than held on to, since
+    // a handler may change it, or destroy the window, on the way.
+    if let Some(saved) = self.saved_fullscreen()?
+      && let Fullscreen::Exclusive(monitor, _) = saved.fullscreen
+    {
+      // Always leave an exclusive mode first, so the next mode is chosen from the desktop mode.
+      change_display_mode(monitor, None)?;
+      if let Some(saved) = self.saved_fullscreen()? {
+        self.set_saved_fullscreen(Some(FullscreenState {
+          fullscreen: Fullscreen::Borderless(monitor),
+          ..saved
+        }))?;
+      }
+    }
+
+    let Some(fullscreen) = fullscreen else {
+      let Some(saved) = self.saved_fullscreen()? else {
+        return Ok(());
+      };
+      self.set_saved_fullscreen(None)?;
+      self.set_window_ptr(WindowPtrIndex::Style, saved.style.bits() as isize)?;
+      self.set_window_ptr(WindowPtrIndex::ExStyle, saved.ex_style.bits() as isize)?;
+      self.set_placement(&saved.placement)?;
+      return self.frame_changed();
+    };
+
+    let entering = self.saved_fullscreen()?.is_none();
+    if entering {
+      let saved = FullscreenState {
+        fullscreen,
+        style: WindowStyle::from_bits_retain(self.get_window_ptr(WindowPtrIndex::Style) as u32),
+        ex_style: ExtendedWindowStyle::from_bits_retain(self.get_window_ptr(WindowPtrIndex::ExStyle) as u32),
+        placement: self.placement()?,
+      };
+      self.set_saved_fullscreen(Some(saved))?;
+    }
+
+    if let Fullscreen::Exclusive(monitor, mode) = &fullscreen
+      && let Err(error) = change_display_mode(*monitor, Some(mode))
+    {
+      if entering {
+        let _ = self.set_saved_fullscreen(None);
+      }
+      return Err(error);
+    }
+
+    // Left fullscreen again while the mode was changing.
+    let Some(saved) = self.saved_fullscreen()? else {
+      return Ok(());
+    };
+    self.set_saved_fullscreen(Some(FullscreenState { fullscreen, ..saved }))?;
+
+    let style = saved.style.difference(WindowStyle::OverlappedWindow);
+    let ex_style = saved.ex_style.difference(
+      ExtendedWindowStyle::WindowEdge
+        | ExtendedWindowStyle::ClientEdge
+        | ExtendedWindowStyle::DialogModalFrame
+        | ExtendedWindowStyle::StaticEdge,
+    );
+    self.set_window_ptr(WindowPtrIndex::Style, style.bits() as isize)?;
+    self.set_window_ptr(WindowPtrIndex::ExStyle, ex_style.bits() as isize)?;
+    self.cover_monitor(fullscreen.monitor())
+  }
+
+  /// Gives exclusive fullscreen windows their display mode back when the app gains focus, and the user
+  /// their desktop mode back when it loses focus or the window is destroyed. Runs after the window
+  /// procedure has seen the message, so it sees `ActivateApp` before the `Size` this causes.
+  pub(crate) fn update_fullscreen(&self, message: &Message) {
+    let active = match message {
+      Message::ActivateApp(activate_app) => activate_app.w.0 != 0,
+      Message::Destroy => false,
+      _ => return,
+    };
+    let Some(Fullscreen::Exclusive(monitor, mode)) = self.fullscreen() else {
+      return;
+    };
+
+    match active {
+      true => {
+        if self.is_minimized() {
+          unsafe { ShowWindow(self.to_ptr(), SW_RESTORE) };
+        }
+        if change_display_mode(monitor, Some(&mode)).is_ok() {
+          let _ = self.cover_monitor(monitor);
+        }
+      },
+      false => {
+        let _ = change_display_mode(monitor, None);
+        if !matches!(message, Message::Destroy) {
+          unsafe { ShowWindow(self.to_ptr(), SW_MINIMIZE) };
+        }
+      },
+    }
+  }
+
+  fn cover_monitor(&self, monitor: Monitor) -> Result<()> {
+    let bounds = monitor.bounds()?;
+    reset_last_error();
+    match unsafe {
+      SetWindowPos(
+        self.to_ptr(),
+        HWND_TOP,
+        bounds.left,
+        bounds.top,
+        bounds.right - bounds.left,
+        bounds.bottom - bounds.top,
+        WindowsAndMessaging::SWP_FRAMECHANGED | WindowsAndMessaging::SWP_NOOWNERZORDER,
+      )
+    } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+
+  fn saved_fullscreen(&self) -> Result<Option<FullscreenState>> {
+    match self.owned_user_data() {
+      Some(data) => Ok(data.fullscreen.get()),
+      None => Err(convert_error(ERROR_NOT_SUPPORTED)),
+    }
+  }
+
+  fn set_saved_fullscreen(&self, saved: Option<FullscreenState>) -> Result<()> {
+    let Some(data) = self.owned_user_data() else {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    };
+    data.fullscreen.set(saved);
+    Ok(())
+  }
+}
@@ -0,0 +1,185 @@
+use {
+  super::{
+    Window,
+    state::window_from_ptr,
+  },
+  crate::{
+    Handle,
+    get_last_error,
+    reset_last_error,
+  },
+  widestring::U16CString,
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::{
+    Win32::{
+      Foundation::{
+        HWND,
+        LPARAM,
+      },
+      UI::WindowsAndMessaging::{
+        self,
+        EnumChildWindows,
+        EnumThreadWindows,
+        EnumWindows,
+        FindWindowExW,
+        GET_ANCESTOR_FLAGS,
+        GET_WINDOW_CMD,
+        GetAncestor,
+        GetClassNameW,
+        GetParent,
+        GetWindow,
+        GetWindowThreadProcessId,
+      },
+    },
+    core::BOOL,
+  },
+};
+
+unsafe extern "system" fn enum_windows(hwnd: HWND, data: LPARAM) -> BOOL {
+  if let Some(windows) = unsafe { (data as *mut Vec<Window>).as_mut() } {
+    windows.push(unsafe { Window::from_ptr(hwnd) });
+  }
+  true.into() // continue enumeration
+}
+
+/// Collects the windows reported by one of the `Enum*Windows` functions. The list is a snapshot, so
+/// windows may have been destroyed by the time they are looked at.
+fn collect_windows(enumerate: impl FnOnce(LPARAM)) -> std::vec::IntoIter<Window> {
+  let mut windows: Vec<Window> = Vec::new();
+  enumerate(&mut windows as *mut _ as LPARAM);
+  windows.into_iter()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Ancestor {
+  /// The parent, but never the owner, unlike [`Window::parent`].
+  Parent,
+  /// The top-level window reached by following parents.
+  Root,
+  /// The window reached by following parents and then owners.
+  RootOwner,
+}
+
+impl Ancestor {
+  const fn to_raw(self) -> GET_ANCESTOR_FLAGS {
+    match self {
+      Ancestor::Parent => WindowsAndMessaging::GA_PARENT,
+      Ancestor::Root => WindowsAndMessaging::GA_ROOT,
+      Ancestor::RootOwner => WindowsAndMessaging::GA_ROOTOWNER,
+    }
+  }
+}
+
+impl Window {
+  /// Every top-level window on the desktop, from the top of the z-order down.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumwindows"]
+  pub fn top_level() -> impl Iterator<Item = Window> {
+    collect_windows(|data| unsafe {
+      EnumWindows(Some(enum_windows), data);
+    })
+  }
+
+  /// Every window created by the thread with the given platform id, e.g. from
+  /// [`Window::get_platform_thread_id`].
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumthreadwindows"]
+  pub fn thread_windows(thread_id: u32) -> impl Iterator<Item = Window> {
+    collect_windows(|data| unsafe {
+      EnumThreadWindows(thread_id, Some(enum_windows), data);
+    })
+  }
+
+  /// Every descendant of this window, not just its direct children.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumchildwindows"]
+  pub fn children(&self) -> impl Iterator<Item = Window> {
+    collect_windows(|data| unsafe {
+      EnumChildWindows(self.to_ptr(), Some(enum_windows), data);
+    })
+  }
+
+  /// Finds the first child of `parent` after `after` whose class and title match. `None` for `parent`
+  /// searches the top-level windows, and `None` for `class` or `title` matches any. Both comparisons
+  /// ignore case.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-findwindowexw"]
+  pub fn find(
+    parent: Option<Window>,
+    after: Option<Window>,
+    class: Option<&str>,
+    title: Option<&str>,
+  ) -> Option<Window> {
+    let class = class.map(U16CString::from_str_truncate);
+    let title = title.map(U16CString::from_str_truncate);
+    window_from_ptr(unsafe {
+      FindWindowExW(
+        parent.map_or(std::ptr::null_mut(), |parent| parent.to_ptr()),
+        after.map_or(std::ptr::null_mut(), |after| after.to_ptr()),
+        class.as_ref().map_or(std::ptr::null(), |class| class.as_ptr()),
+        title.as_ref().map_or(std::ptr::null(), |title| title.as_ptr()),
+      )
+    })
+  }
+
+  /// The parent of a child window, or the owner of a top-level one.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getparent"]
+  pub fn parent(&self) -> Option<Window> {
+    window_from_ptr(unsafe { GetParent(self.to_ptr()) })
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getancestor"]
+  pub fn ancestor(&self, ancestor: Ancestor) -> Option<Window> {
+    window_from_ptr(unsafe { GetAncestor(self.to_ptr(), ancestor.to_raw()) })
+  }
+
+  fn get_window(&self, cmd: GET_WINDOW_CMD) -> Option<Window> {
+    window_from_ptr(unsafe { GetWindow(self.to_ptr(), cmd) })
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindow"]
+  pub fn owner(&self) -> Option<Window> {
+    self.get_window(WindowsAndMessaging::GW_OWNER)
+  }
+
+  /// The window below this one in the z-order.
+  pub fn next_sibling(&self) -> Option<Window> {
+    self.get_window(WindowsAndMessaging::GW_HWNDNEXT)
+  }
+
+  /// The window above this one in the z-order.
+  pub fn previous_sibling(&self) -> Option<Window> {
+    self.get_window(WindowsAndMessaging::GW_HWNDPREV)
+  }
+
+  /// The child at the top of the z-order.
+  pub fn first_child(&self) -> Option<Window> {
+    self.get_window(WindowsAndMessaging::GW_CHILD)
+  }
+
+  /// The id of the process that created the window.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowthreadprocessid"]
+  pub fn get_process_id(&self) -> Option<u32> {
+    let mut process_id = 0;
+    match unsafe { GetWindowThreadProcessId(self.to_ptr(), &mut process_id) } {
+      0 => None,
+      _ => Some(process_id),
+    }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getclassnamew"]
+  pub fn get_class_name(&self) -> Result<String> {
+    // Class names are limited to 256 characters.
+    let mut buffer = [0u16; 257];
+    reset_last_error();
+    match unsafe { GetClassNameW(self.to_ptr(), buffer.as_mut_ptr(), buffer.len() as i32) } {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      len => Ok(String::from_utf16_lossy(&buffer[..len as usize])),
+    }
+  }
+}
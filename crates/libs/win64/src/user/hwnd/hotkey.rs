@@ -0,0 +1,63 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    convert_error,
+    high_word,
+    input::keyboard::shortcut::Shortcut,
+    last_error,
+    low_word,
+    reset_last_error,
+    user::HotKeyMessage,
+  },
+  keyboard_types::Code,
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::ERROR_INVALID_PARAMETER,
+    UI::Input::KeyboardAndMouse::{
+      RegisterHotKey,
+      UnregisterHotKey,
+      VIRTUAL_KEY,
+    },
+  },
+};
+
+impl Window {
+  /// Posts a `HotKey` message to this window whenever `shortcut` is pressed, even while the window
+  /// doesn't have focus. Unless `repeat` is set, holding the shortcut down only notifies once.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerhotkey"]
+  pub fn register_hotkey(&self, id: i32, shortcut: Shortcut, repeat: bool) -> Result<()> {
+    let Some(vkey) = shortcut.virtual_key() else {
+      return Err(convert_error(ERROR_INVALID_PARAMETER));
+    };
+    reset_last_error();
+    match unsafe { RegisterHotKey(self.to_ptr(), id, shortcut.hotkey_modifiers(repeat), vkey as u32) } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-unregisterhotkey"]
+  pub fn unregister_hotkey(&self, id: i32) -> Result<()> {
+    reset_last_error();
+    match unsafe { UnregisterHotKey(self.to_ptr(), id) } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+}
+
+impl HotKeyMessage {
+  /// The id given to [`Window::register_hotkey`], or one of the negative system hotkey ids.
+  pub fn id(&self) -> i32 {
+    self.w.0 as i32
+  }
+
+  pub fn shortcut(&self) -> Option<Shortcut> {
+    let l = self.l.0 as u32;
+    let modifiers = Shortcut::modifiers_from_hotkey(low_word(l) as u32);
+    Shortcut::from_virtual_key(modifiers, high_word(l) as VIRTUAL_KEY)
+      .filter(|shortcut| shortcut.code != Code::Unidentified)
+  }
+}
@@ -0,0 +1,69 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    user::{
+      GetIconMessage,
+      Icon,
+      IconSize,
+      LParam,
+      Message,
+      SetIconMessage,
+      WParam,
+    },
+  },
+};
+
+fn icon_from_raw(raw: isize) -> Option<Icon> {
+  match raw {
+    0 => None,
+    raw => Some(unsafe { Icon::from_raw(raw as usize) }),
+  }
+}
+
+impl SetIconMessage {
+  pub fn new(size: IconSize, icon: Option<Icon>) -> Self {
+    Self { w: WParam(size.to_raw()), l: LParam(icon.map_or(0, |icon| icon.to_raw() as isize)) }
+  }
+
+  pub fn icon_size(&self) -> IconSize {
+    IconSize::from_raw(self.w.0)
+  }
+
+  /// `None` removes the icon.
+  pub fn icon(&self) -> Option<Icon> {
+    icon_from_raw(self.l.0)
+  }
+}
+
+impl GetIconMessage {
+  pub fn new(size: IconSize, dpi: u32) -> Self {
+    Self { w: WParam(size.to_raw()), l: LParam(dpi as isize) }
+  }
+
+  pub fn icon_size(&self) -> IconSize {
+    IconSize::from_raw(self.w.0)
+  }
+
+  /// The DPI the icon is wanted for.
+  pub fn dpi(&self) -> u32 {
+    self.l.0 as u32
+  }
+}
+
+impl Window {
+  /// Sets the taskbar (`big`) and title bar (`small`) icons. `None` removes an icon, leaving the one of the
+  /// window class. Windows don't take ownership, so the icons have to outlive the window.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-seticon"]
+  pub fn set_icon(&self, big: Option<Icon>, small: Option<Icon>) {
+    self.send_message(Message::SetIcon(SetIconMessage::new(IconSize::Big, big)));
+    self.send_message(Message::SetIcon(SetIconMessage::new(IconSize::Small, small)));
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/winmsg/wm-geticon"]
+  pub fn get_icon(&self, size: IconSize) -> Option<Icon> {
+    let dpi = self.dpi();
+    icon_from_raw(self.send_message(Message::GetIcon(GetIconMessage::new(size, dpi))).0)
+  }
+}
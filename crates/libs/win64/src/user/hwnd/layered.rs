@@ -0,0 +1,210 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    convert_error,
+    get_last_error,
+    last_error,
+    pixel::to_premultiplied_bgra,
+    reset_last_error,
+    user::ExtendedWindowStyle,
+  },
+  dpi::{
+    PhysicalPosition,
+    PhysicalSize,
+  },
+  rgb::{
+    RGB8,
+    RGBA8,
+  },
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::{
+    Foundation::{
+      COLORREF,
+      ERROR_INVALID_PARAMETER,
+      POINT,
+      SIZE,
+    },
+    Graphics::Gdi::{
+      AC_SRC_ALPHA,
+      AC_SRC_OVER,
+      BI_RGB,
+      BITMAPINFO,
+      BITMAPINFOHEADER,
+      BLENDFUNCTION,
+      CreateCompatibleDC,
+      CreateDIBSection,
+      DIB_RGB_COLORS,
+      DeleteDC,
+      DeleteObject,
+      GetDC,
+      ReleaseDC,
+      SelectObject,
+    },
+    UI::WindowsAndMessaging::{
+      GetLayeredWindowAttributes,
+      LAYERED_WINDOW_ATTRIBUTES_FLAGS,
+      LWA_ALPHA,
+      LWA_COLORKEY,
+      SetLayeredWindowAttributes,
+      ULW_ALPHA,
+      UpdateLayeredWindow,
+    },
+  },
+};
+
+const fn to_colorref(color: RGB8) -> COLORREF {
+  ((color.b as u32) << 16) | ((color.g as u32) << 8) | color.r as u32
+}
+
+const fn from_colorref(color: COLORREF) -> RGB8 {
+  RGB8 { r: color as u8, g: (color >> 8) as u8, b: (color >> 16) as u8 }
+}
+
+impl Window {
+  /// Makes the whole window translucent with `alpha`, and/or fully transparent wherever it is painted
+  /// `colorkey`. `None` for both makes the window opaque again. Adds `ExtendedWindowStyle::Layered` if
+  /// the window doesn't have it yet.
+  ///
+  /// A window can't use this and [`Window::update_layered_window`] at the same time; remove and re-add
+  /// `Layered` to switch.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setlayeredwindowattributes"]
+  pub fn set_layered_attributes(&self, alpha: Option<u8>, colorkey: Option<RGB8>) -> Result<()> {
+    self.ensure_layered()?;
+    let mut flags: LAYERED_WINDOW_ATTRIBUTES_FLAGS = 0;
+    // Opaque is an alpha of 255, since leaving out both flags doesn't change anything.
+    if alpha.is_some() || colorkey.is_none() {
+      flags |= LWA_ALPHA;
+    }
+    if colorkey.is_some() {
+      flags |= LWA_COLORKEY;
+    }
+    reset_last_error();
+    match unsafe {
+      SetLayeredWindowAttributes(
+        self.to_ptr(),
+        colorkey.map_or(0, to_colorref),
+        alpha.unwrap_or(u8::MAX),
+        flags,
+      )
+    } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+
+  /// The alpha and colorkey set with [`Window::set_layered_attributes`]. Fails for windows that present
+  /// with [`Window::update_layered_window`].
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getlayeredwindowattributes"]
+  pub fn layered_attributes(&self) -> Result<(Option<u8>, Option<RGB8>)> {
+    let (mut colorkey, mut alpha, mut flags) = (0, 0, 0);
+    reset_last_error();
+    match unsafe { GetLayeredWindowAttributes(self.to_ptr(), &mut colorkey, &mut alpha, &mut flags) } {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      _ => Ok((
+        ((flags & LWA_ALPHA) != 0).then_some(alpha),
+        ((flags & LWA_COLORKEY) != 0).then(|| from_colorref(colorkey)),
+      )),
+    }
+  }
+
+  /// Replaces the window's contents with `width * height` pixels, row by row from the top left, and
+  /// resizes the window to match. Each pixel's alpha shapes the window: fully transparent pixels also
+  /// let clicks through. `alpha` fades the whole window on top of that. Moves the window to `position`,
+  /// in screen coordinates, unless it is `None`.
+  ///
+  /// The pixels are premultiplied here, so pass them as they'd be drawn anywhere else. Adds
+  /// `ExtendedWindowStyle::Layered` if the window doesn't have it yet. The window doesn't get `Paint`
+  /// messages while it is presented this way, so call this again whenever the contents change.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-updatelayeredwindow"]
+  pub fn update_layered_window(
+    &self,
+    pixels: &[RGBA8],
+    size: PhysicalSize<u32>,
+    position: Option<PhysicalPosition<i32>>,
+    alpha: u8,
+  ) -> Result<()> {
+    if size.width == 0 || size.height == 0 || pixels.len() != size.width as usize * size.height as usize {
+      return Err(convert_error(ERROR_INVALID_PARAMETER));
+    }
+    self.ensure_layered()?;
+
+    let info = BITMAPINFO {
+      bmiHeader: BITMAPINFOHEADER {
+        biSize: size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: size.width as i32,
+        // Negative for rows from the top down.
+        biHeight: -(size.height as i32),
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let bytes = to_premultiplied_bgra(pixels);
+
+    let screen = unsafe { GetDC(std::ptr::null_mut()) };
+    let memory = unsafe { CreateCompatibleDC(screen) };
+    let mut bits = std::ptr::null_mut();
+    reset_last_error();
+    let bitmap =
+      unsafe { CreateDIBSection(memory, &info, DIB_RGB_COLORS, &mut bits, std::ptr::null_mut(), 0) };
+
+    let result = match bitmap.is_null() {
+      true => Err(get_last_error().unwrap_or(Error::empty())),
+      false => {
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), bits.cast::<u8>(), bytes.len()) };
+        let previous = unsafe { SelectObject(memory, bitmap) };
+        let blend = BLENDFUNCTION {
+          BlendOp: AC_SRC_OVER as u8,
+          BlendFlags: 0,
+          SourceConstantAlpha: alpha,
+          AlphaFormat: AC_SRC_ALPHA as u8,
+        };
+        let position = position.map(|position| POINT { x: position.x, y: position.y });
+        let size = SIZE { cx: size.width as i32, cy: size.height as i32 };
+        let source = POINT { x: 0, y: 0 };
+        let updated = unsafe {
+          UpdateLayeredWindow(
+            self.to_ptr(),
+            screen,
+            position.as_ref().map_or(std::ptr::null(), |position| position as *const POINT),
+            &size,
+            memory,
+            &source,
+            0,
+            &blend,
+            ULW_ALPHA,
+          )
+        };
+        unsafe { SelectObject(memory, previous) };
+        match updated {
+          0 => Err(get_last_error().unwrap_or(Error::empty())),
+          _ => Ok(()),
+        }
+      },
+    };
+
+    unsafe {
+      DeleteObject(bitmap);
+      DeleteDC(memory);
+      ReleaseDC(std::ptr::null_mut(), screen);
+    }
+    result
+  }
+
+  fn ensure_layered(&self) -> Result<()> {
+    let ex_style = self.ex_style();
+    match ex_style.contains(ExtendedWindowStyle::Layered) {
+      true => Ok(()),
+      false => self.set_ex_style(ex_style | ExtendedWindowStyle::Layered),
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    Rect,
+    get_last_error,
+    last_error,
+    reset_last_error,
+    user::Monitor,
+  },
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetWindowPlacement,
+    SetWindowPlacement,
+    WINDOWPLACEMENT,
+  },
+};
+
+pub use crate::placement::{
+  PlacementState,
+  WindowPlacement,
+};
+
+/// The work areas of the connected monitors in workspace coordinates, for [`WindowPlacement::fit_to`].
+pub fn workspace_work_areas() -> Result<Vec<Rect>> {
+  let primary = Monitor::primary().work_area()?;
+  Monitor::available()
+    .into_iter()
+    .map(|monitor| {
+      monitor.work_area().map(|area| Rect {
+        left: area.left - primary.left,
+        top: area.top - primary.top,
+        right: area.right - primary.left,
+        bottom: area.bottom - primary.top,
+      })
+    })
+    .collect()
+}
+
+impl Window {
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowplacement"]
+  pub fn placement(&self) -> Result<WindowPlacement> {
+    let mut placement = WINDOWPLACEMENT { length: size_of::<WINDOWPLACEMENT>() as u32, ..Default::default() };
+    reset_last_error();
+    match unsafe { GetWindowPlacement(self.to_ptr(), &mut placement) } {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      _ => Ok(WindowPlacement::from_raw(&placement)),
+    }
+  }
+
+  /// Applies `placement` as is. Use [`Window::restore_placement`] for placements that were saved in an
+  /// earlier run, since the monitors may have changed since.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowplacement"]
+  pub fn set_placement(&self, placement: &WindowPlacement) -> Result<()> {
+    let placement = placement.to_raw();
+    reset_last_error();
+    match unsafe { SetWindowPlacement(self.to_ptr(), &placement) } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+
+  /// Applies `placement` after moving it onto a connected monitor, so the window doesn't reopen
+  /// off-screen after the monitor it was on was unplugged.
+  pub fn restore_placement(&self, placement: &WindowPlacement) -> Result<()> {
+    self.set_placement(&placement.fit_to(&workspace_work_areas()?))
+  }
+}
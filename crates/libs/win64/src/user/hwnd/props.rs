@@ -0,0 +1,283 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    convert_error,
+    last_error,
+    reset_last_error,
+  },
+  std::{
+    any::Any,
+    cell::RefCell,
+    rc::Rc,
+  },
+  widestring::{
+    U16CStr,
+    U16CString,
+  },
+  windows_result::Result,
+  windows_sys::{
+    Win32::{
+      Foundation::{
+        ERROR_INVALID_PARAMETER,
+        ERROR_NOT_SUPPORTED,
+        HANDLE,
+        HWND,
+        LPARAM,
+        LRESULT,
+        WPARAM,
+      },
+      UI::{
+        Shell::{
+          DefSubclassProc,
+          RemoveWindowSubclass,
+          SetWindowSubclass,
+        },
+        WindowsAndMessaging::{
+          EnumPropsExW,
+          GetPropW,
+          RemovePropW,
+          SetPropW,
+          WM_NCDESTROY,
+        },
+      },
+    },
+    core::{
+      BOOL,
+      PCWSTR,
+    },
+  },
+};
+
+/// The key of a window property.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PropKey {
+  /// Compared without regard to case, like the global atom the system turns it into.
+  Name(String),
+  /// A global atom, e.g. from `GlobalAddAtomW`, which is quicker to look up than a name.
+  Atom(u16),
+}
+
+impl PropKey {
+  fn with_raw<R>(&self, f: impl FnOnce(PCWSTR) -> R) -> R {
+    match self {
+      PropKey::Name(name) => f(U16CString::from_str_truncate(name).as_ptr()),
+      // MAKEINTATOM
+      PropKey::Atom(atom) => f(*atom as usize as PCWSTR),
+    }
+  }
+
+  /// # Safety
+  /// `raw` has to be an atom or point to a nul-terminated string.
+  unsafe fn from_raw(raw: PCWSTR) -> Self {
+    match raw as usize >> 16 {
+      0 => PropKey::Atom(raw as usize as u16),
+      _ => PropKey::Name(unsafe { U16CStr::from_ptr_str(raw) }.to_string_lossy()),
+    }
+  }
+}
+
+impl From<&str> for PropKey {
+  fn from(name: &str) -> Self {
+    PropKey::Name(name.to_owned())
+  }
+}
+
+impl From<String> for PropKey {
+  fn from(name: String) -> Self {
+    PropKey::Name(name)
+  }
+}
+
+impl From<u16> for PropKey {
+  fn from(atom: u16) -> Self {
+    PropKey::Atom(atom)
+  }
+}
+
+unsafe extern "system" fn enum_props(_: HWND, key: PCWSTR, value: HANDLE, data: usize) -> BOOL {
+  if let Some(props) = unsafe { (data as *mut Vec<(PropKey, usize)>).as_mut() } {
+    props.push((unsafe { PropKey::from_raw(key) }, value as usize));
+  }
+  true.into() // continue enumeration
+}
+
+impl Window {
+  /// Stores `value` in the window's property list under `key`, replacing what was there. Any window can
+  /// have properties, including ones created by other libraries or processes.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setpropw"]
+  pub fn set_prop(&self, key: impl Into<PropKey>, value: usize) -> Result<()> {
+    let key = key.into();
+    let previous = self.get_prop_raw(&key);
+    reset_last_error();
+    match key.with_raw(|key| unsafe { SetPropW(self.to_ptr(), key, value as HANDLE) }) {
+      0 => last_error(),
+      _ => {
+        self.release_boxed_prop(previous);
+        Ok(())
+      },
+    }
+  }
+
+  /// The value stored under `key`, or `None` if there isn't one. A value of zero can't be told apart from
+  /// a missing one.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getpropw"]
+  pub fn prop(&self, key: impl Into<PropKey>) -> Option<usize> {
+    let value = self.get_prop_raw(&key.into());
+    (!value.is_null()).then_some(value as usize)
+  }
+
+  /// Removes `key` from the window's property list and returns its value. Boxed values are dropped
+  /// instead, and return `None`.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-removepropw"]
+  pub fn remove_prop(&self, key: impl Into<PropKey>) -> Option<usize> {
+    let value = key.into().with_raw(|key| unsafe { RemovePropW(self.to_ptr(), key) });
+    match value.is_null() || self.release_boxed_prop(value) {
+      true => None,
+      false => Some(value as usize),
+    }
+  }
+
+  /// Stores a Rust value under `key`, replacing what was there. The window keeps a reference to it until
+  /// the property is replaced or removed, or until `NcDestroy`, whichever comes first.
+  ///
+  /// Works on any window owned by the current thread, including ones created outside of this crate.
+  /// Boxed values aren't thread-safe, so only that thread can set or get them.
+  pub fn set_boxed_prop<T: Any>(&self, key: impl Into<PropKey>, value: T) -> Result<()> {
+    if !self.is_current_thread() {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    }
+    let registry = match self.boxed_props() {
+      Some(registry) => registry,
+      None => self.insert_boxed_props()?,
+    };
+    let value: Rc<dyn Any> = Rc::new(value);
+    let handle = Box::into_raw(Box::new(value)) as HANDLE;
+    // Registered first, since replacing the previous value drops it, which may run any code, up to
+    // destroying the window and the registry with it.
+    registry.handles.borrow_mut().push(handle);
+    if let Err(error) = self.set_prop(key, handle as usize) {
+      self.release_boxed_prop(handle);
+      return Err(error);
+    }
+    Ok(())
+  }
+
+  /// The value stored under `key` with [`Window::set_boxed_prop`], if it is a `T`.
+  pub fn boxed_prop<T: Any>(&self, key: impl Into<PropKey>) -> Option<Rc<T>> {
+    let handle = self.get_prop_raw(&key.into());
+    // Other code may have put anything under the key, so only values this crate boxed are looked into.
+    if handle.is_null() || !self.boxed_props()?.handles.borrow().contains(&handle) {
+      return None;
+    }
+    unsafe { &*(handle as *const Rc<dyn Any>) }.clone().downcast().ok()
+  }
+
+  /// Every property of the window, in no particular order. Boxed values show up as their addresses, next
+  /// to the private property that keeps track of them.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumpropsexw"]
+  pub fn props(&self) -> impl Iterator<Item = (PropKey, usize)> {
+    let mut props: Vec<(PropKey, usize)> = Vec::new();
+    unsafe { EnumPropsExW(self.to_ptr(), Some(enum_props), &mut props as *mut _ as isize) };
+    props.into_iter()
+  }
+
+  /// Creates the registry of boxed values for a window that doesn't have one yet, along with the subclass
+  /// that frees it.
+  fn insert_boxed_props(&self) -> Result<&BoxedProps> {
+    let registry = Box::into_raw(Box::new(BoxedProps { handles: RefCell::new(Vec::new()) }));
+    if let Err(error) = self.set_prop(BoxedProps::key(), registry as usize) {
+      drop(unsafe { Box::from_raw(registry) });
+      return Err(error);
+    }
+    if unsafe { SetWindowSubclass(self.to_ptr(), Some(boxed_props_procedure), 0, 0) } == 0 {
+      self.remove_prop(BoxedProps::key());
+      drop(unsafe { Box::from_raw(registry) });
+      return Err(convert_error(ERROR_INVALID_PARAMETER));
+    }
+    Ok(unsafe { &*registry })
+  }
+
+  /// Removes and drops every boxed value, along with the registry, since the properties have to be gone
+  /// by the time the window is.
+  fn remove_boxed_props(&self) {
+    let registry = BoxedProps::key().with_raw(|key| unsafe { RemovePropW(self.to_ptr(), key) });
+    if registry.is_null() {
+      return;
+    }
+    let registry = unsafe { Box::from_raw(registry as *mut BoxedProps) };
+    let handles = registry.handles.take();
+    for (key, _) in self.props().filter(|(_, value)| handles.contains(&(*value as HANDLE))) {
+      key.with_raw(|key| unsafe { RemovePropW(self.to_ptr(), key) });
+    }
+    // Dropped after every borrow is let go, since the values may run any code on their way out.
+    for handle in handles {
+      drop(unsafe { Box::from_raw(handle as *mut Rc<dyn Any>) });
+    }
+  }
+
+  fn get_prop_raw(&self, key: &PropKey) -> HANDLE {
+    key.with_raw(|key| unsafe { GetPropW(self.to_ptr(), key) })
+  }
+
+  /// Drops `handle` if it is a boxed value, and returns whether it was.
+  fn release_boxed_prop(&self, handle: HANDLE) -> bool {
+    let Some(registry) = self.boxed_props() else {
+      return false;
+    };
+    let mut handles = registry.handles.borrow_mut();
+    let Some(index) = handles.iter().position(|boxed| *boxed == handle) else {
+      return false;
+    };
+    handles.swap_remove(index);
+    drop(handles);
+    drop(unsafe { Box::from_raw(handle as *mut Rc<dyn Any>) });
+    true
+  }
+
+  /// The registry of boxed values, if the window is owned by this thread and has one.
+  fn boxed_props(&self) -> Option<&BoxedProps> {
+    if !self.is_current_thread() {
+      return None;
+    }
+    let registry = self.get_prop_raw(&BoxedProps::key()) as *const BoxedProps;
+    unsafe { registry.as_ref() }
+  }
+}
+
+/// The values boxed by [`Window::set_boxed_prop`] on one window. It is kept in a private property
+/// rather than the window's user data, which belongs to whoever created the window.
+struct BoxedProps {
+  handles: RefCell<Vec<HANDLE>>,
+}
+
+impl BoxedProps {
+  /// Versioned, since another copy of the crate in the same process may lay the registry out differently.
+  fn key() -> PropKey {
+    PropKey::Name(concat!("win64 ", env!("CARGO_PKG_VERSION"), " boxed props").to_owned())
+  }
+}
+
+/// Frees the boxed values once the window and its other procedures are done with `NcDestroy`.
+///
+/// # Safety
+/// Only called by comctl32 for the subclass added by [`Window::insert_boxed_props`].
+unsafe extern "system" fn boxed_props_procedure(
+  hwnd: HWND,
+  msg: u32,
+  w_param: WPARAM,
+  l_param: LPARAM,
+  _: usize,
+  _: usize,
+) -> LRESULT {
+  let result = unsafe { DefSubclassProc(hwnd, msg, w_param, l_param) };
+  if msg == WM_NCDESTROY {
+    unsafe { RemoveWindowSubclass(hwnd, Some(boxed_props_procedure), 0) };
+    unsafe { Window::from_ptr(hwnd) }.remove_boxed_props();
+  }
+  result
+}
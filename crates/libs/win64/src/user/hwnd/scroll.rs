@@ -0,0 +1,80 @@
+use {
+  super::Window,
+  crate::{
+    input::scroll::{
+      ScrollAmount,
+      ScrollAxis,
+      WheelAccumulator,
+      WheelScroll,
+    },
+    signed_high_word,
+    user::{
+      MouseHWheelMessage,
+      MouseWheelMessage,
+    },
+  },
+};
+
+impl Window {
+  /// Feeds a wheel delta into this window's accumulator and returns the whole lines or pages it adds
+  /// up to. See [`WheelAccumulator::scroll`].
+  pub fn accumulate_wheel(&self, axis: ScrollAxis, delta: i32, per_notch: WheelScroll) -> ScrollAmount {
+    self.with_wheel_accumulator(|wheel| wheel.scroll(axis, delta, per_notch))
+  }
+
+  /// Like [`Window::accumulate_wheel`], but converts the delta into pixels. See
+  /// [`WheelAccumulator::scroll_pixels`].
+  pub fn accumulate_wheel_pixels(
+    &self,
+    axis: ScrollAxis,
+    delta: i32,
+    per_notch: WheelScroll,
+    line_size: f64,
+    page_size: f64,
+  ) -> f64 {
+    self.with_wheel_accumulator(|wheel| wheel.scroll_pixels(axis, delta, per_notch, line_size, page_size))
+  }
+
+  /// Throws away any partial scrolling this window has collected, e.g. when it loses focus.
+  pub fn reset_wheel(&self) {
+    self.with_wheel_accumulator(WheelAccumulator::reset)
+  }
+
+  /// Windows that weren't created by this crate, or belong to another thread, have no accumulator, so
+  /// each call starts from scratch for them.
+  fn with_wheel_accumulator<T>(&self, f: impl FnOnce(&mut WheelAccumulator) -> T) -> T {
+    let Some(data) = self.owned_user_data() else {
+      return f(&mut WheelAccumulator::new());
+    };
+
+    let wheel = &data.wheel;
+    let mut accumulator = wheel.get();
+    let result = f(&mut accumulator);
+    wheel.set(accumulator);
+    result
+  }
+}
+
+impl MouseWheelMessage {
+  /// Positive deltas scroll away from the user. One notch is `WHEEL_DELTA`.
+  pub fn delta(&self) -> i32 {
+    signed_high_word(self.w.0 as i32) as i32
+  }
+
+  /// Shorthand for [`Window::accumulate_wheel`] along the vertical axis.
+  pub fn scroll(&self, window: &Window, per_notch: WheelScroll) -> ScrollAmount {
+    window.accumulate_wheel(ScrollAxis::Vertical, self.delta(), per_notch)
+  }
+}
+
+impl MouseHWheelMessage {
+  /// Positive deltas scroll to the right. One notch is `WHEEL_DELTA`.
+  pub fn delta(&self) -> i32 {
+    signed_high_word(self.w.0 as i32) as i32
+  }
+
+  /// Shorthand for [`Window::accumulate_wheel`] along the horizontal axis.
+  pub fn scroll(&self, window: &Window, per_notch: WheelScroll) -> ScrollAmount {
+    window.accumulate_wheel(ScrollAxis::Horizontal, self.delta(), per_notch)
+  }
+}
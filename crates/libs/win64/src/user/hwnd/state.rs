@@ -0,0 +1,179 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    get_last_error,
+    last_error,
+    reset_last_error,
+  },
+  bitflags::bitflags,
+  std::time::Duration,
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::HWND,
+    UI::{
+      Input::KeyboardAndMouse::{
+        EnableWindow,
+        GetActiveWindow,
+        GetFocus,
+        IsWindowEnabled,
+        SetFocus,
+      },
+      WindowsAndMessaging::{
+        self,
+        ASFW_ANY,
+        AllowSetForegroundWindow,
+        BringWindowToTop,
+        FLASHWINFO,
+        FLASHWINFO_FLAGS,
+        FlashWindowEx,
+        IsIconic,
+        IsWindowVisible,
+        IsZoomed,
+        SetForegroundWindow,
+      },
+    },
+  },
+};
+
+bitflags! {
+  /// What [`Window::flash`] flashes and for how long. Leaving out both `Timer` flags flashes `count`
+  /// times; the empty set stops flashing.
+  #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+  pub struct FlashFlags: u32 {
+    const Caption = WindowsAndMessaging::FLASHW_CAPTION;
+    const Tray = WindowsAndMessaging::FLASHW_TRAY;
+    const All = WindowsAndMessaging::FLASHW_ALL;
+    /// Flash until stopped.
+    const Timer = WindowsAndMessaging::FLASHW_TIMER;
+    /// Flash until the window comes to the foreground.
+    const TimerNoForeground = WindowsAndMessaging::FLASHW_TIMERNOFG;
+  }
+}
+
+impl FlashFlags {
+  #[inline]
+  pub const fn to_raw(self) -> FLASHWINFO_FLAGS {
+    self.bits()
+  }
+}
+
+impl Default for FlashFlags {
+  fn default() -> Self {
+    Self::empty()
+  }
+}
+
+pub(crate) fn window_from_ptr(hwnd: HWND) -> Option<Window> {
+  match hwnd.is_null() {
+    true => None,
+    false => Some(unsafe { Window::from_ptr(hwnd) }),
+  }
+}
+
+/// Lets another process bring its windows to the foreground, e.g. one that this process is about to hand
+/// off to. `None` allows every process. Only works while this process is allowed to set the foreground
+/// window itself.
+///
+#[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-allowsetforegroundwindow"]
+pub fn allow_set_foreground_window(process_id: Option<u32>) -> Result<()> {
+  reset_last_error();
+  match unsafe { AllowSetForegroundWindow(process_id.unwrap_or(ASFW_ANY)) } {
+    0 => last_error(),
+    _ => Ok(()),
+  }
+}
+
+impl Window {
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-iswindowvisible"]
+  pub fn is_visible(&self) -> bool {
+    unsafe { IsWindowVisible(self.to_ptr()) != 0 }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-isiconic"]
+  pub fn is_minimized(&self) -> bool {
+    unsafe { IsIconic(self.to_ptr()) != 0 }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-iszoomed"]
+  pub fn is_maximized(&self) -> bool {
+    unsafe { IsZoomed(self.to_ptr()) != 0 }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-iswindowenabled"]
+  pub fn is_enabled(&self) -> bool {
+    unsafe { IsWindowEnabled(self.to_ptr()) != 0 }
+  }
+
+  /// Enables or disables mouse and keyboard input to the window. Returns whether the window was enabled
+  /// before.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enablewindow"]
+  pub fn enable(&self, enable: bool) -> bool {
+    // EnableWindow reports whether the window was *disabled*.
+    unsafe { EnableWindow(self.to_ptr(), enable.into()) == 0 }
+  }
+
+  /// Gives the window keyboard focus. Returns the window that had it before, if any.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setfocus"]
+  pub fn set_focus(&self) -> Result<Option<Window>> {
+    reset_last_error();
+    let previous = unsafe { SetFocus(self.to_ptr()) };
+    match (previous.is_null(), get_last_error()) {
+      (true, Some(error)) => Err(error),
+      _ => Ok(window_from_ptr(previous)),
+    }
+  }
+
+  /// The window with keyboard focus, if it belongs to the calling thread's message queue.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getfocus"]
+  pub fn get_focus() -> Option<Window> {
+    window_from_ptr(unsafe { GetFocus() })
+  }
+
+  /// The active top-level window, if it belongs to the calling thread's message queue.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getactivewindow"]
+  pub fn get_active_window() -> Option<Window> {
+    window_from_ptr(unsafe { GetActiveWindow() })
+  }
+
+  /// Brings the window to the foreground and activates it. Returns `false` when Windows refused, which it
+  /// does unless the calling process received the last input event or was allowed with
+  /// [`allow_set_foreground_window`]; the taskbar button flashes instead.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setforegroundwindow"]
+  pub fn set_foreground(&self) -> bool {
+    unsafe { SetForegroundWindow(self.to_ptr()) != 0 }
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-bringwindowtotop"]
+  pub fn bring_to_top(&self) -> Result<()> {
+    reset_last_error();
+    match unsafe { BringWindowToTop(self.to_ptr()) } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+
+  /// Flashes the caption and/or taskbar button `count` times, `interval` apart. `None` uses the caret
+  /// blink rate. Returns whether the window was drawn as active before the call.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-flashwindowex"]
+  pub fn flash(&self, flags: FlashFlags, count: u32, interval: Option<Duration>) -> bool {
+    let info = FLASHWINFO {
+      cbSize: size_of::<FLASHWINFO>() as u32,
+      hwnd: self.to_ptr(),
+      dwFlags: flags.to_raw(),
+      uCount: count,
+      dwTimeout: interval.map_or(0, |interval| interval.as_millis().min(u32::MAX as u128) as u32),
+    };
+    unsafe { FlashWindowEx(&info) != 0 }
+  }
+
+  pub fn stop_flashing(&self) {
+    self.flash(FlashFlags::empty(), 0, None);
+  }
+}
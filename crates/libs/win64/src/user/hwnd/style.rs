@@ -0,0 +1,143 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    last_error,
+    reset_last_error,
+    user::{
+      ExtendedWindowStyle,
+      StyleChangedMessage,
+      StyleChangingMessage,
+      WindowPtrIndex,
+      WindowStyle,
+    },
+  },
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::HWND,
+    UI::WindowsAndMessaging::{
+      self,
+      GWL_EXSTYLE,
+      HWND_NOTOPMOST,
+      HWND_TOPMOST,
+      STYLESTRUCT,
+      SetWindowPos,
+    },
+  },
+};
+
+/// The old and new styles reported by `WM_STYLECHANGING` and `WM_STYLECHANGED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StyleChange {
+  Style { old: WindowStyle, new: WindowStyle },
+  ExStyle { old: ExtendedWindowStyle, new: ExtendedWindowStyle },
+}
+
+impl StyleChange {
+  /// # Safety
+  /// `l` has to point to the `STYLESTRUCT` of a `WM_STYLECHANGING` or `WM_STYLECHANGED` message.
+  unsafe fn from_params(w: usize, l: isize) -> Self {
+    let styles = unsafe { *(l as *const STYLESTRUCT) };
+    match w as i32 {
+      GWL_EXSTYLE => Self::ExStyle {
+        old: ExtendedWindowStyle::from_bits_retain(styles.styleOld),
+        new: ExtendedWindowStyle::from_bits_retain(styles.styleNew),
+      },
+      _ => Self::Style {
+        old: WindowStyle::from_bits_retain(styles.styleOld),
+        new: WindowStyle::from_bits_retain(styles.styleNew),
+      },
+    }
+  }
+}
+
+impl StyleChangingMessage {
+  pub fn change(&self) -> StyleChange {
+    unsafe { StyleChange::from_params(self.w.0, self.l.0) }
+  }
+}
+
+impl StyleChangedMessage {
+  pub fn change(&self) -> StyleChange {
+    unsafe { StyleChange::from_params(self.w.0, self.l.0) }
+  }
+}
+
+impl Window {
+  pub fn style(&self) -> WindowStyle {
+    WindowStyle::from_bits_retain(self.get_window_ptr(WindowPtrIndex::Style) as u32)
+  }
+
+  /// Replaces the window's style and redraws the frame to match.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowlongptrw"]
+  pub fn set_style(&self, style: WindowStyle) -> Result<()> {
+    self.set_window_ptr(WindowPtrIndex::Style, style.to_raw() as isize)?;
+    self.frame_changed()
+  }
+
+  pub fn ex_style(&self) -> ExtendedWindowStyle {
+    ExtendedWindowStyle::from_bits_retain(self.get_window_ptr(WindowPtrIndex::ExStyle) as u32)
+  }
+
+  /// Replaces the window's extended style and redraws the frame to match.
+  ///
+  /// `TopMost` can't be changed through `SetWindowLongPtrW`, so a change to it is applied by moving the
+  /// window into or out of the topmost band with `SetWindowPos` instead.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowlongptrw"]
+  pub fn set_ex_style(&self, ex_style: ExtendedWindowStyle) -> Result<()> {
+    let topmost = ex_style.contains(ExtendedWindowStyle::TopMost);
+    let topmost_changed = topmost != self.ex_style().contains(ExtendedWindowStyle::TopMost);
+    self.set_window_ptr(WindowPtrIndex::ExStyle, ex_style.to_raw() as isize)?;
+    match topmost_changed {
+      true => self.set_topmost(topmost),
+      false => self.frame_changed(),
+    }
+  }
+
+  /// Moves the window into or out of the band of windows that stay above all others.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowpos"]
+  pub fn set_topmost(&self, topmost: bool) -> Result<()> {
+    let insert_after = match topmost {
+      true => HWND_TOPMOST,
+      false => HWND_NOTOPMOST,
+    };
+    self.set_window_pos_flags(insert_after, WindowsAndMessaging::SWP_FRAMECHANGED)
+  }
+
+  /// Tells the window its frame changed, so a new style takes effect.
+  pub(crate) fn frame_changed(&self) -> Result<()> {
+    self.set_window_pos_flags(
+      std::ptr::null_mut(),
+      WindowsAndMessaging::SWP_FRAMECHANGED | WindowsAndMessaging::SWP_NOZORDER,
+    )
+  }
+
+  fn set_window_pos_flags(
+    &self,
+    insert_after: HWND,
+    flags: WindowsAndMessaging::SET_WINDOW_POS_FLAGS,
+  ) -> Result<()> {
+    reset_last_error();
+    match unsafe {
+      SetWindowPos(
+        self.to_ptr(),
+        insert_after,
+        0,
+        0,
+        0,
+        0,
+        flags
+          | WindowsAndMessaging::SWP_NOMOVE
+          | WindowsAndMessaging::SWP_NOSIZE
+          | WindowsAndMessaging::SWP_NOOWNERZORDER
+          | WindowsAndMessaging::SWP_NOACTIVATE,
+      )
+    } {
+      0 => last_error(),
+      _ => Ok(()),
+    }
+  }
+}
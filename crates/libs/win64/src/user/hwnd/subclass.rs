@@ -0,0 +1,143 @@
+use {
+  super::Window,
+  crate::{
+    Handle,
+    convert_error,
+    user::{
+      LParam,
+      LResult,
+      Message,
+      WParam,
+      WindowProcedure,
+      procedure::ProcedureCell,
+    },
+  },
+  std::cell::Cell,
+  windows_result::Result,
+  windows_sys::Win32::{
+    Foundation::{
+      ERROR_INVALID_PARAMETER,
+      ERROR_NOT_SUPPORTED,
+      HWND,
+      LPARAM,
+      LRESULT,
+      WPARAM,
+    },
+    UI::{
+      Shell::{
+        DefSubclassProc,
+        RemoveWindowSubclass,
+        SetWindowSubclass,
+      },
+      WindowsAndMessaging::WM_NCDESTROY,
+    },
+  },
+};
+
+/// Identifies a procedure added with [`Window::subclass`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubclassId(usize);
+
+struct Subclass {
+  proc: ProcedureCell,
+  /// Removing the subclass while a message is going through it only drops it once that is done.
+  removed: Cell<bool>,
+  /// How many messages are going through the subclass, counting the procedures below it they are passed
+  /// on to, since those can destroy the window or remove the subclass too.
+  depth: Cell<u32>,
+}
+
+impl Subclass {
+  /// Drops the subclass if it has been removed and no message is going through it.
+  ///
+  /// # Safety
+  /// `subclass` has to be one leaked by [`Window::subclass`], and can't be used after this.
+  unsafe fn release(subclass: *mut Subclass) {
+    let this = unsafe { &*subclass };
+    if this.removed.get() && this.depth.get() == 0 {
+      drop(unsafe { Box::from_raw(subclass) });
+    }
+  }
+}
+
+/// # Safety
+/// Only called by comctl32 for subclasses added by [`Window::subclass`], with `data` pointing to the
+/// [`Subclass`] leaked there.
+unsafe extern "system" fn subclass_procedure(
+  hwnd: HWND,
+  msg: u32,
+  w_param: WPARAM,
+  l_param: LPARAM,
+  _: usize,
+  data: usize,
+) -> LRESULT {
+  let window = unsafe { Window::from_ptr(hwnd) };
+  let message = Message::new(msg.into(), WParam(w_param), LParam(l_param));
+  let subclass = data as *mut Subclass;
+  let depth = unsafe { &(*subclass).depth };
+  depth.set(depth.get() + 1);
+
+  let result = unsafe { (*subclass).proc.dispatch(&window, &message) };
+
+  if msg == WM_NCDESTROY {
+    // The procedures further down still need to see `NcDestroy`, e.g. to free the crate's user data.
+    unsafe { RemoveWindowSubclass(hwnd, Some(subclass_procedure), data) };
+    unsafe { (*subclass).removed.set(true) };
+  }
+  let result = match (msg, result) {
+    (WM_NCDESTROY, _) | (_, None) => unsafe { DefSubclassProc(hwnd, msg, w_param, l_param) },
+    (_, Some(LResult(result))) => result,
+  };
+
+  let depth = unsafe { &(*subclass).depth };
+  depth.set(depth.get() - 1);
+  unsafe { Subclass::release(subclass) };
+  result
+}
+
+impl Window {
+  /// Puts `procedure` in front of the window's own procedure. Messages it returns `None` for go on to
+  /// the previous procedure, so subclasses stack, with the latest one seeing messages first.
+  ///
+  /// Works on any window owned by the current thread, including built-in controls and windows created
+  /// outside of this crate. The subclass is removed on `NcDestroy`, after `procedure` has seen it; its
+  /// result for `NcDestroy` is ignored so the procedures below it get the message too. Messages sent
+  /// while `procedure` is busy are nested, as described on [`WindowProcedure`].
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-setwindowsubclass"]
+  pub fn subclass(&self, procedure: impl WindowProcedure + 'static) -> Result<SubclassId> {
+    if !self.is_current_thread() {
+      return Err(convert_error(ERROR_NOT_SUPPORTED));
+    }
+    let subclass = Box::into_raw(Box::new(Subclass {
+      proc: ProcedureCell::new(Box::new(procedure)),
+      removed: Cell::new(false),
+      depth: Cell::new(0),
+    })) as usize;
+    // The address of the procedure doubles as the id, since it is unique while the subclass exists.
+    match unsafe { SetWindowSubclass(self.to_ptr(), Some(subclass_procedure), subclass, subclass) } {
+      0 => {
+        drop(unsafe { Box::from_raw(subclass as *mut Subclass) });
+        Err(convert_error(ERROR_INVALID_PARAMETER))
+      },
+      _ => Ok(SubclassId(subclass)),
+    }
+  }
+
+  /// Removes a procedure added with [`Window::subclass`] and drops it. A subclass can remove itself from
+  /// inside its own procedure, in which case it is dropped once it returns.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-removewindowsubclass"]
+  pub fn remove_subclass(&self, id: SubclassId) -> Result<()> {
+    // Fails for ids that aren't installed on this window, so `id` is only trusted as a pointer after.
+    match unsafe { RemoveWindowSubclass(self.to_ptr(), Some(subclass_procedure), id.0) } {
+      0 => Err(convert_error(ERROR_INVALID_PARAMETER)),
+      _ => {
+        let subclass = id.0 as *mut Subclass;
+        unsafe { (*subclass).removed.set(true) };
+        unsafe { Subclass::release(subclass) };
+        Ok(())
+      },
+    }
+  }
+}
@@ -0,0 +1,251 @@
+use {
+  crate::{
+    Handle,
+    convert_error,
+    declare_handle,
+    get_last_error,
+    ico::IcoEntry,
+    pixel::to_bgra,
+    user::Instance,
+  },
+  dpi::PhysicalPosition,
+  rgb::RGBA8,
+  std::{
+    ops::Deref,
+    path::Path,
+  },
+  widestring::U16CString,
+  windows_result::{
+    Error,
+    Result,
+  },
+  windows_sys::Win32::{
+    Foundation::{
+      ERROR_INVALID_PARAMETER,
+      HANDLE,
+    },
+    Graphics::Gdi::{
+      CreateBitmap,
+      DeleteObject,
+    },
+    UI::{
+      HiDpi::GetSystemMetricsForDpi,
+      WindowsAndMessaging::{
+        self,
+        CreateIconFromResourceEx,
+        CreateIconIndirect,
+        DestroyIcon,
+        HICON,
+        ICONINFO,
+        IMAGE_FLAGS,
+        IMAGE_ICON,
+        LR_DEFAULTCOLOR,
+        LR_LOADFROMFILE,
+        LoadImageW,
+      },
+    },
+  },
+};
+
+declare_handle!(
+  Icon,
+  alias = "HICON",
+  doc = "https://learn.microsoft.com/en-us/windows/win32/winprog/windows-data-types#hicon"
+);
+
+impl Icon {
+  /// Only for icons that were created rather than loaded with `LR_SHARED`. Prefer [`OwnedIcon`], which
+  /// does this on drop.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-destroyicon"]
+  pub fn destroy(self) {
+    unsafe { DestroyIcon(self.to_ptr()) };
+  }
+}
+
+/// Which of a window's icons a `SetIcon` or `GetIcon` message is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IconSize {
+  /// Shown in the title bar.
+  Small,
+  /// Shown in the taskbar and the Alt+Tab switcher.
+  Big,
+  /// The small icon Windows made up from the big one when no small icon was set. Only for `GetIcon`.
+  Small2,
+}
+
+impl IconSize {
+  pub const fn from_raw(raw: usize) -> Self {
+    match raw as u32 {
+      WindowsAndMessaging::ICON_BIG => Self::Big,
+      WindowsAndMessaging::ICON_SMALL2 => Self::Small2,
+      _ => Self::Small,
+    }
+  }
+
+  pub const fn to_raw(self) -> usize {
+    (match self {
+      Self::Small => WindowsAndMessaging::ICON_SMALL,
+      Self::Big => WindowsAndMessaging::ICON_BIG,
+      Self::Small2 => WindowsAndMessaging::ICON_SMALL2,
+    }) as usize
+  }
+
+  /// The width and height Windows draws this icon at on a monitor with the given DPI, for picking an
+  /// image out of an `.ico` file.
+  pub fn pixels_for_dpi(self, dpi: u32) -> u32 {
+    let metric = match self {
+      Self::Big => WindowsAndMessaging::SM_CXICON,
+      Self::Small | Self::Small2 => WindowsAndMessaging::SM_CXSMICON,
+    };
+    unsafe { GetSystemMetricsForDpi(metric, dpi) as u32 }
+  }
+}
+
+/// An [`Icon`] that is destroyed when dropped.
+///
+/// Windows don't take ownership of their icons, so keep this alive for as long as a window or class uses
+/// the icon.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OwnedIcon(Icon);
+
+impl Drop for OwnedIcon {
+  fn drop(&mut self) {
+    self.0.destroy();
+  }
+}
+
+impl Deref for OwnedIcon {
+  type Target = Icon;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+fn icon_result(icon: *mut std::ffi::c_void) -> Result<OwnedIcon> {
+  match icon.is_null() {
+    true => Err(get_last_error().unwrap_or(Error::empty())),
+    false => Ok(OwnedIcon(unsafe { Icon::from_ptr(icon) })),
+  }
+}
+
+impl OwnedIcon {
+  /// # Safety
+  /// `icon` has to be a valid icon that nothing else destroys, e.g. one that wasn't loaded with
+  /// `LR_SHARED`.
+  pub unsafe fn from_icon(icon: Icon) -> Self {
+    Self(icon)
+  }
+
+  /// Gives up ownership without destroying the icon.
+  pub fn into_icon(self) -> Icon {
+    let icon = self.0;
+    std::mem::forget(self);
+    icon
+  }
+
+  /// Creates an icon from `width * height` pixels, row by row from the top left. Pixels with an alpha of
+  /// zero are transparent.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createiconindirect"]
+  pub fn from_rgba(pixels: &[RGBA8], width: u32, height: u32) -> Result<Self> {
+    icon_result(create_icon_indirect(pixels, width, height, None)?)
+  }
+
+  /// Creates an icon from one image of an `.ico` file, e.g. the one [`crate::ico::IcoFile::best_for_dpi`]
+  /// picked.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createiconfromresourceex"]
+  pub fn from_ico_entry(entry: &IcoEntry) -> Result<Self> {
+    const VERSION: u32 = 0x00030000;
+    let icon = unsafe {
+      CreateIconFromResourceEx(
+        entry.data.as_ptr(),
+        entry.data.len() as u32,
+        true.into(),
+        VERSION,
+        entry.width as i32,
+        entry.height as i32,
+        LR_DEFAULTCOLOR,
+      )
+    };
+    icon_result(icon)
+  }
+
+  /// Loads the image closest to `size` pixels from an `.ico` file. `None` uses the size of big icons.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-loadimagew"]
+  pub fn from_file(path: impl AsRef<Path>, size: Option<u32>) -> Result<Self> {
+    let path = U16CString::from_os_str_truncate(path.as_ref());
+    load_image(std::ptr::null_mut(), path.as_ptr(), size, LR_LOADFROMFILE)
+  }
+
+  /// Loads the image closest to `size` pixels from an icon resource of `instance`. `None` uses the size
+  /// of big icons.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-loadimagew"]
+  pub fn from_resource(instance: Instance, name: &str, size: Option<u32>) -> Result<Self> {
+    let name = U16CString::from_str_truncate(name);
+    load_image(instance.to_ptr(), name.as_ptr(), size, LR_DEFAULTCOLOR)
+  }
+
+  /// Like [`OwnedIcon::from_resource`], for resources with a numeric id.
+  pub fn from_resource_id(instance: Instance, id: u16, size: Option<u32>) -> Result<Self> {
+    // MAKEINTRESOURCE
+    load_image(instance.to_ptr(), id as usize as *const u16, size, LR_DEFAULTCOLOR)
+  }
+}
+
+/// Builds the color and mask bitmaps for an icon, or for a cursor when there is a `hotspot`.
+pub(crate) fn create_icon_indirect(
+  pixels: &[RGBA8],
+  width: u32,
+  height: u32,
+  hotspot: Option<PhysicalPosition<u32>>,
+) -> Result<HICON> {
+  if width == 0 || height == 0 || pixels.len() != width as usize * height as usize {
+    return Err(convert_error(ERROR_INVALID_PARAMETER));
+  }
+
+  let color = to_bgra(pixels);
+  // Monochrome bitmap rows are padded to a whole number of 16-bit words.
+  let stride = (width as usize).div_ceil(16) * 2;
+  let mut mask = vec![0u8; stride * height as usize];
+  for (index, pixel) in pixels.iter().enumerate() {
+    if pixel.a == 0 {
+      let (row, column) = (index / width as usize, index % width as usize);
+      mask[row * stride + column / 8] |= 0x80 >> (column % 8);
+    }
+  }
+
+  let (width, height) = (width as i32, height as i32);
+  let info = ICONINFO {
+    fIcon: hotspot.is_none().into(),
+    xHotspot: hotspot.map_or(0, |hotspot| hotspot.x),
+    yHotspot: hotspot.map_or(0, |hotspot| hotspot.y),
+    hbmMask: unsafe { CreateBitmap(width, height, 1, 1, mask.as_ptr().cast()) },
+    hbmColor: unsafe { CreateBitmap(width, height, 1, 32, color.as_ptr().cast()) },
+  };
+  let icon = match info.hbmMask.is_null() || info.hbmColor.is_null() {
+    true => std::ptr::null_mut(),
+    false => unsafe { CreateIconIndirect(&info) },
+  };
+  // The icon keeps its own copies of the bitmaps.
+  unsafe {
+    DeleteObject(info.hbmMask);
+    DeleteObject(info.hbmColor);
+  }
+  Ok(icon)
+}
+
+fn load_image(
+  instance: HANDLE,
+  name: *const u16,
+  size: Option<u32>,
+  flags: IMAGE_FLAGS,
+) -> Result<OwnedIcon> {
+  // Zero for both sizes makes `LoadImageW` use the size of the first image instead of `SM_CXICON`.
+  let size = size.unwrap_or_else(|| IconSize::Big.pixels_for_dpi(crate::user::system_dpi())) as i32;
+  icon_result(unsafe { LoadImageW(instance, name, IMAGE_ICON, size, size, flags) })
+}
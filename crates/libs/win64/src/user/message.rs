@@ -21,16 +21,17 @@ use {
       keyboard::{
         destructure_key_lparam,
         get_kbd_state,
-        get_location,
-        key::Key,
         layout::{
-          LAYOUT_CACHE,
-          WindowsModifiers,
+          KeyboardLayout,
+          LayoutCache,
         },
         new_ex_scancode,
-        scancode_to_code,
       },
-      mouse::mouse_event,
+      keymap::RawKeyInput,
+      mouse::{
+        mouse_event,
+        mouse_modifier_state,
+      },
     },
     low_word,
   },
@@ -38,13 +39,6 @@ use {
     PhysicalPosition,
     PhysicalSize,
   },
-  keyboard_types::{
-    Code,
-    KeyState,
-    Location,
-    Modifiers,
-    NamedKey,
-  },
   pointer_types::mouse::MouseEvent,
   std::{
     any::Any,
@@ -71,7 +65,6 @@ use {
         MAPVK_VK_TO_VSC_EX,
         MapVirtualKeyExW,
         VIRTUAL_KEY,
-        VK_NUMLOCK,
       },
       WindowsAndMessaging::{
         self,
@@ -96,6 +89,11 @@ use {
   },
 };
 pub use {
+  crate::input::keymap::{
+    KeyEvent,
+    ModifierState,
+    ModifiersChangedEvent,
+  },
   get::*,
   peek::*,
 };
@@ -711,6 +709,13 @@ impl Message {
     Self::MOUSE_MESSAGES.contains(&self.id().to_raw())
   }
 
+  /// The side-aware modifiers held when a mouse message was sent, including moves and wheel turns, or
+  /// `None` for other messages. Button events carry the same state in their `modifiers`, without the
+  /// sides.
+  pub fn mouse_modifier_state(&self) -> Option<ModifierState> {
+    self.is_mouse().then(|| mouse_modifier_state(self.w()))
+  }
+
   #[inline]
   pub const fn quit_requested(&self) -> bool {
     matches!(self, Message::Destroy)
@@ -843,102 +848,45 @@ impl MessageHandler for CreateMessage {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct KeyEvent {
-  pub state: KeyState,
-  pub key: Key,
-  pub code: Code,
-  pub location: Location,
-  pub modifiers: Modifiers,
-  pub repeat: bool,
-  pub key_without_modifiers: Key,
+/// Collects what `KeyEvent` translation needs from a key message and the current keyboard state.
+fn raw_key_input(w: WParam, l: LParam, is_pressed: bool, hkl: u64) -> RawKeyInput {
+  let lparam_struct = destructure_key_lparam(l.0);
+  let vkey = w.0 as VIRTUAL_KEY;
+  let scancode = if lparam_struct.scancode == 0 {
+    // In some cases (often with media keys) the device reports a scancode of 0 but a
+    // valid virtual key. In these cases we obtain the scancode from the virtual key.
+    unsafe { MapVirtualKeyExW(vkey as u32, MAPVK_VK_TO_VSC_EX, hkl as HKL) as u16 }
+  } else {
+    new_ex_scancode(lparam_struct.scancode, lparam_struct.extended)
+  };
+
+  RawKeyInput { vkey, scancode, is_pressed, is_repeat: lparam_struct.is_repeat, kbd_state: get_kbd_state() }
 }
 
 impl KeyEvent {
   fn new(w: WParam, l: LParam, is_pressed: bool) -> Self {
-    let mut layouts = LAYOUT_CACHE.lock().unwrap();
-    const NO_MODS: WindowsModifiers = WindowsModifiers::empty();
-
-    let modifiers = layouts.get_agnostic_mods();
-    let (_, layout) = layouts.get_current_layout();
-    let lparam_struct = destructure_key_lparam(l);
-    let vkey = w.0 as VIRTUAL_KEY;
-    let scancode = if lparam_struct.scancode == 0 {
-      // In some cases (often with media keys) the device reports a scancode of 0 but a
-      // valid virtual key. In these cases we obtain the scancode from the virtual key.
-      unsafe { MapVirtualKeyExW(vkey as u32, MAPVK_VK_TO_VSC_EX, layout.hkl as HKL) as u16 }
-    } else {
-      new_ex_scancode(lparam_struct.scancode, lparam_struct.extended)
-    };
-    let code = scancode_to_code(scancode as u32);
-    let location = get_location(scancode, layout.hkl as HKL);
-
-    let kbd_state = get_kbd_state();
-    let mods = WindowsModifiers::active_modifiers(&kbd_state);
-    let mods_without_ctrl = mods.remove_only_ctrl();
-    let num_lock_on = kbd_state[VK_NUMLOCK as usize] & 1 != 0;
-
-    // On Windows Ctrl+NumLock = Pause (and apparently Ctrl+Pause -> NumLock). In these cases
-    // the KeyCode still stores the real key, so in the name of consistency across platforms, we
-    // circumvent this mapping and force the key values to match the keycode.
-    // For more on this, read the article by Raymond Chen, titled:
-    // "Why does Ctrl+ScrollLock cancel dialogs?"
-    // https://devblogs.microsoft.com/oldnewthing/20080211-00/?p=23503
-    let code_as_key = if mods.contains(WindowsModifiers::CONTROL) {
-      match code {
-        Code::NumLock => Some(Key::Named(NamedKey::NumLock)),
-        Code::Pause => Some(Key::Named(NamedKey::Pause)),
-        _ => None,
-      }
-    } else {
-      None
-    };
-
-    let preliminary_logical_key = layout.get_key(mods_without_ctrl, num_lock_on, vkey, &code);
-    // FIXME(madsmtm): Is the `chars != " "` check desired here?
-    let key_is_char = matches!(&preliminary_logical_key, Key::Character(chars) if chars != " ");
-
-    let key = if let Some(key) = code_as_key.clone() {
-      key
-    } else if is_pressed && key_is_char && !mods.contains(WindowsModifiers::CONTROL) {
-      // In some cases we want to use the UNICHAR text for logical_key in order to allow
-      // dead keys to have an effect on the character reported by `logical_key`.
-      preliminary_logical_key
-    } else {
-      preliminary_logical_key
-    };
-    let key_without_modifiers = if let Some(key) = code_as_key {
-      key
-    } else {
-      match layout.get_key(NO_MODS, false, vkey, &code) {
-        // We convert dead keys into their character.
-        // The reason for this is that `key_without_modifiers` is designed for key-bindings,
-        // but the US International layout treats `'` (apostrophe) as a dead key and the
-        // regular US layout treats it a character. In order for a single binding
-        // configuration to work with both layouts, we forward each dead key as a character.
-        Key::Dead(k) => {
-          if let Some(ch) = k {
-            // I'm avoiding the heap allocation. I don't want to talk about it :(
-            let mut utf8 = [0; 4];
-            let s = ch.encode_utf8(&mut utf8);
-            Key::Character(s.to_string())
-          } else {
-            Key::Named(NamedKey::Unidentified)
-          }
-        },
-        key => key,
-      }
-    };
-
-    Self {
-      state: if is_pressed { KeyState::Down } else { KeyState::Up },
-      key,
-      code,
-      location,
-      modifiers,
-      repeat: lparam_struct.is_repeat,
-      key_without_modifiers,
-    }
+    let (hkl, layout) = LayoutCache::lock().get_current_layout();
+    Self::translate(&layout, &raw_key_input(w, l, is_pressed, hkl))
+  }
+}
+
+impl ModifiersChangedEvent {
+  fn new(w: WParam, l: LParam, is_pressed: bool) -> Option<Self> {
+    let (hkl, layout) = LayoutCache::lock().get_current_layout();
+    Self::from_key_input(&raw_key_input(w, l, is_pressed, hkl), layout.has_alt_graph)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutChangedEvent {
+  pub layout: KeyboardLayout,
+  pub charset: u32,
+}
+
+impl InputLangChangeMessage {
+  pub fn event(&self) -> LayoutChangedEvent {
+    let layout = unsafe { KeyboardLayout::from_raw(self.l.0 as usize) };
+    LayoutChangedEvent { layout, charset: self.w.0 as u32 }
   }
 }
 
@@ -1018,12 +966,42 @@ impl KeyDownMessage {
   pub fn event(&self) -> KeyEvent {
     KeyEvent::new(self.w, self.l, true)
   }
+
+  pub fn modifiers_changed(&self) -> Option<ModifiersChangedEvent> {
+    ModifiersChangedEvent::new(self.w, self.l, true)
+  }
+}
+
+impl SysKeyDownMessage {
+  /// Alt, F10 and keys pressed while alt is held arrive as system keys.
+  pub fn event(&self) -> KeyEvent {
+    KeyEvent::new(self.w, self.l, true)
+  }
+
+  pub fn modifiers_changed(&self) -> Option<ModifiersChangedEvent> {
+    ModifiersChangedEvent::new(self.w, self.l, true)
+  }
 }
 
 impl KeyUpMessage {
   pub fn event(&self) -> KeyEvent {
     KeyEvent::new(self.w, self.l, false)
   }
+
+  pub fn modifiers_changed(&self) -> Option<ModifiersChangedEvent> {
+    ModifiersChangedEvent::new(self.w, self.l, false)
+  }
+}
+
+impl SysKeyUpMessage {
+  /// Alt, F10 and keys pressed while alt is held arrive as system keys.
+  pub fn event(&self) -> KeyEvent {
+    KeyEvent::new(self.w, self.l, false)
+  }
+
+  pub fn modifiers_changed(&self) -> Option<ModifiersChangedEvent> {
+    ModifiersChangedEvent::new(self.w, self.l, false)
+  }
 }
 
 impl CharMessage {
@@ -1073,14 +1051,14 @@ impl DpiChangedMessage {
 }
 
 impl CreateMessage {
-  pub fn use_dark_mode(&self, window: &Window, enable: bool) {
-    window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(enable));
+  pub fn use_dark_mode(&self, window: &Window, enable: bool) -> windows_result::Result<()> {
+    window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(enable))
   }
 }
 
 impl SettingChangeMessage {
-  pub fn use_dark_mode(&self, window: &Window, enable: bool) {
-    window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(enable));
+  pub fn use_dark_mode(&self, window: &Window, enable: bool) -> windows_result::Result<()> {
+    window.dwm_set_window_attribute(DwmWindowAttribute::UseImmersiveDarkMode(enable))
   }
 }
 
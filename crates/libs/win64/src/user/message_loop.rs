@@ -4,8 +4,14 @@ use {
     Msg,
     PeekMessageFlags,
   },
-  crate::user::PeekResult,
-  std::ops::RangeInclusive,
+  crate::user::{
+    PeekResult,
+    take_procedure_panic,
+  },
+  std::{
+    ops::RangeInclusive,
+    panic,
+  },
   windows_result::Error,
 };
 
@@ -42,6 +48,8 @@ impl MessageLoop {
     self
   }
 
+  /// Translates and dispatches messages until `Quit`. A panic caught in a window procedure on this thread
+  /// is resumed here, once the message that caused it has been dispatched.
   pub fn run(self) {
     if self.peek.is_some() {
       self.peek(|msg| {
@@ -49,6 +57,7 @@ impl MessageLoop {
           msg.translate();
           msg.dispatch();
         }
+        resume_procedure_panic();
       });
     } else {
       self.get(|msg| {
@@ -56,15 +65,23 @@ impl MessageLoop {
           msg.translate();
           msg.dispatch();
         }
+        resume_procedure_panic();
       });
     }
   }
 
+  /// Like [`MessageLoop::run`], with `f` handling each message instead.
   pub fn run_with(self, f: impl Fn(MessageLoopResult)) {
     if self.peek.is_some() {
-      self.peek(f);
+      self.peek(|msg| {
+        f(msg);
+        resume_procedure_panic();
+      });
     } else {
-      self.get(f);
+      self.get(|msg| {
+        f(msg);
+        resume_procedure_panic();
+      });
     }
   }
 
@@ -94,3 +111,9 @@ pub enum MessageLoopResult {
   Get(Msg),
   Err(Error),
 }
+
+fn resume_procedure_panic() {
+  if let Some(payload) = take_procedure_panic() {
+    panic::resume_unwind(payload);
+  }
+}
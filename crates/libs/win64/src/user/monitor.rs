@@ -6,25 +6,63 @@ use {
   crate::{
     Handle,
     ORIGIN_POINT,
+    Rect,
+    convert_error,
     declare_handle,
+    get_last_error,
+    reset_last_error,
+  },
+  dpi::{
+    PhysicalPosition,
+    PhysicalSize,
   },
-  dpi::PhysicalPosition,
   std::collections::VecDeque,
+  windows_result::{
+    Error,
+    Result,
+  },
   windows_sys::{
     Win32::{
+      Devices::Display::{
+        DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+        DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+        DISPLAYCONFIG_DEVICE_INFO_HEADER,
+        DISPLAYCONFIG_MODE_INFO,
+        DISPLAYCONFIG_PATH_INFO,
+        DISPLAYCONFIG_SOURCE_DEVICE_NAME,
+        DISPLAYCONFIG_TARGET_DEVICE_NAME,
+        DisplayConfigGetDeviceInfo,
+        GetDisplayConfigBufferSizes,
+        QDC_ONLY_ACTIVE_PATHS,
+        QueryDisplayConfig,
+      },
       Foundation::{
+        ERROR_INSUFFICIENT_BUFFER,
+        ERROR_SUCCESS,
         LPARAM,
         POINT,
         RECT,
+        WIN32_ERROR,
       },
       Graphics::Gdi::{
+        DEVMODEW,
+        DM_BITSPERPEL,
+        DM_DISPLAYFREQUENCY,
+        DM_PELSHEIGHT,
+        DM_PELSWIDTH,
+        ENUM_CURRENT_SETTINGS,
+        ENUM_DISPLAY_SETTINGS_MODE,
         EnumDisplayMonitors,
+        EnumDisplaySettingsExW,
+        GetMonitorInfoW,
         HDC,
         HMONITOR,
         MONITOR_DEFAULTTONEAREST,
         MONITOR_DEFAULTTONULL,
         MONITOR_DEFAULTTOPRIMARY,
         MONITOR_FROM_FLAGS,
+        MONITORINFO,
+        MONITORINFOEXW,
         MonitorFromPoint,
         MonitorFromWindow,
       },
@@ -33,7 +71,10 @@ use {
           GetDpiForMonitor,
           MDT_EFFECTIVE_DPI,
         },
-        WindowsAndMessaging,
+        WindowsAndMessaging::{
+          self,
+          MONITORINFOF_PRIMARY,
+        },
       },
     },
     core::BOOL,
@@ -128,3 +169,200 @@ impl Monitor {
     dpi_to_scale_factor(self.dpi())
   }
 }
+
+/// Reads a fixed size, null terminated buffer such as `szDevice`.
+fn from_wide_buffer(buffer: &[u16]) -> String {
+  let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+  String::from_utf16_lossy(&buffer[..len])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonitorInfo {
+  /// The whole monitor, in virtual screen coordinates.
+  pub bounds: Rect,
+  /// The part of the monitor not covered by the taskbar or docked toolbars.
+  pub work_area: Rect,
+  pub is_primary: bool,
+  /// The GDI device name, e.g. `\\.\DISPLAY1`.
+  pub device_name: String,
+}
+
+/// A display mode as reported by `EnumDisplaySettingsExW`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+  pub size: PhysicalSize<u32>,
+  pub bit_depth: u32,
+  /// In hertz. Zero or one means the hardware default.
+  pub refresh_rate: u32,
+}
+
+impl VideoMode {
+  pub fn from_raw(mode: &DEVMODEW) -> Self {
+    Self {
+      size: PhysicalSize::new(mode.dmPelsWidth, mode.dmPelsHeight),
+      bit_depth: mode.dmBitsPerPel,
+      refresh_rate: mode.dmDisplayFrequency,
+    }
+  }
+
+  pub fn to_raw(&self) -> DEVMODEW {
+    DEVMODEW {
+      dmSize: size_of::<DEVMODEW>() as u16,
+      dmFields: DM_PELSWIDTH | DM_PELSHEIGHT | DM_BITSPERPEL | DM_DISPLAYFREQUENCY,
+      dmPelsWidth: self.size.width,
+      dmPelsHeight: self.size.height,
+      dmBitsPerPel: self.bit_depth,
+      dmDisplayFrequency: self.refresh_rate,
+      ..Default::default()
+    }
+  }
+}
+
+impl Monitor {
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getmonitorinfow"]
+  pub fn info(&self) -> Result<MonitorInfo> {
+    let mut info = MONITORINFOEXW {
+      monitorInfo: MONITORINFO { cbSize: size_of::<MONITORINFOEXW>() as u32, ..Default::default() },
+      ..Default::default()
+    };
+    reset_last_error();
+    match unsafe { GetMonitorInfoW(self.to_ptr(), (&raw mut info).cast()) } {
+      0 => Err(get_last_error().unwrap_or(Error::empty())),
+      _ => Ok(MonitorInfo {
+        bounds: info.monitorInfo.rcMonitor.into(),
+        work_area: info.monitorInfo.rcWork.into(),
+        is_primary: (info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY) != 0,
+        device_name: from_wide_buffer(&info.szDevice),
+      }),
+    }
+  }
+
+  pub fn bounds(&self) -> Result<Rect> {
+    self.info().map(|info| info.bounds)
+  }
+
+  pub fn work_area(&self) -> Result<Rect> {
+    self.info().map(|info| info.work_area)
+  }
+
+  pub fn is_primary(&self) -> Result<bool> {
+    self.info().map(|info| info.is_primary)
+  }
+
+  pub fn device_name(&self) -> Result<String> {
+    self.info().map(|info| info.device_name)
+  }
+
+  fn video_mode(device_name: &[u16], index: ENUM_DISPLAY_SETTINGS_MODE) -> Option<VideoMode> {
+    let mut mode = DEVMODEW { dmSize: size_of::<DEVMODEW>() as u16, ..Default::default() };
+    match unsafe { EnumDisplaySettingsExW(device_name.as_ptr(), index, &mut mode, 0) } {
+      0 => None,
+      _ => Some(VideoMode::from_raw(&mode)),
+    }
+  }
+
+  fn wide_device_name(&self) -> Result<Vec<u16>> {
+    Ok(self.device_name()?.encode_utf16().chain(Some(0)).collect())
+  }
+
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumdisplaysettingsexw"]
+  pub fn current_video_mode(&self) -> Result<VideoMode> {
+    let device_name = self.wide_device_name()?;
+    reset_last_error();
+    Self::video_mode(&device_name, ENUM_CURRENT_SETTINGS)
+      .ok_or_else(|| get_last_error().unwrap_or(Error::empty()))
+  }
+
+  /// Every mode the monitor supports, with duplicates removed. Modes that differ only in settings this
+  /// crate doesn't expose, such as orientation or scaling, are reported by Windows more than once.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumdisplaysettingsexw"]
+  pub fn video_modes(&self) -> Result<Vec<VideoMode>> {
+    let device_name = self.wide_device_name()?;
+    let mut modes = Vec::new();
+    for index in 0.. {
+      let Some(mode) = Self::video_mode(&device_name, index) else {
+        break;
+      };
+      if !modes.contains(&mode) {
+        modes.push(mode);
+      }
+    }
+    Ok(modes)
+  }
+
+  /// The name shown in the display settings, e.g. "DELL U2720Q". Returns `None` when Windows has no
+  /// name for the monitor, which is common for built-in panels.
+  ///
+  #[doc = "https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-querydisplayconfig"]
+  pub fn friendly_name(&self) -> Result<Option<String>> {
+    let device_name = self.device_name()?;
+
+    let mut paths: Vec<DISPLAYCONFIG_PATH_INFO> = Vec::new();
+    let mut modes: Vec<DISPLAYCONFIG_MODE_INFO> = Vec::new();
+    loop {
+      let (mut path_count, mut mode_count) = (0, 0);
+      win32_result(unsafe {
+        GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count)
+      })?;
+      paths.resize(path_count as usize, Default::default());
+      modes.resize(mode_count as usize, Default::default());
+
+      // The configuration can change between the two calls, in which case the buffers are too small.
+      match unsafe {
+        QueryDisplayConfig(
+          QDC_ONLY_ACTIVE_PATHS,
+          &mut path_count,
+          paths.as_mut_ptr(),
+          &mut mode_count,
+          modes.as_mut_ptr(),
+          std::ptr::null_mut(),
+        )
+      } {
+        ERROR_INSUFFICIENT_BUFFER => continue,
+        error => win32_result(error)?,
+      }
+      paths.truncate(path_count as usize);
+      break;
+    }
+
+    for path in &paths {
+      let mut source = DISPLAYCONFIG_SOURCE_DEVICE_NAME {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+          r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+          size: size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32,
+          adapterId: path.sourceInfo.adapterId,
+          id: path.sourceInfo.id,
+        },
+        ..Default::default()
+      };
+      win32_result(unsafe { DisplayConfigGetDeviceInfo(&mut source.header) } as WIN32_ERROR)?;
+      if from_wide_buffer(&source.viewGdiDeviceName) != device_name {
+        continue;
+      }
+
+      let mut target = DISPLAYCONFIG_TARGET_DEVICE_NAME {
+        header: DISPLAYCONFIG_DEVICE_INFO_HEADER {
+          r#type: DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+          size: size_of::<DISPLAYCONFIG_TARGET_DEVICE_NAME>() as u32,
+          adapterId: path.targetInfo.adapterId,
+          id: path.targetInfo.id,
+        },
+        ..Default::default()
+      };
+      win32_result(unsafe { DisplayConfigGetDeviceInfo(&mut target.header) } as WIN32_ERROR)?;
+      let name = from_wide_buffer(&target.monitorFriendlyDeviceName);
+      return Ok((!name.is_empty()).then_some(name));
+    }
+
+    Ok(None)
+  }
+}
+
+/// The display configuration functions return their error instead of setting the last error.
+fn win32_result(error: WIN32_ERROR) -> Result<()> {
+  match error {
+    ERROR_SUCCESS => Ok(()),
+    error => Err(convert_error(error)),
+  }
+}
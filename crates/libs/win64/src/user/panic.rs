@@ -0,0 +1,72 @@
+//!
+//! Keeping panics in window procedures from unwinding into Win32.
+//!
+//! Unwinding out of an `extern "system"` function aborts the process, so a panic while handling a message
+//! is caught where the window procedure is called, and the message gets the default handling instead. The
+//! panic is kept for the thread and resumed by [`MessageLoop::run`](crate::user::MessageLoop::run) once
+//! Win32 has returned, which unwinds as usual from there.
+//!
+
+use {
+  crate::user::Window,
+  std::{
+    any::Any,
+    cell::Cell,
+    panic::{
+      self,
+      AssertUnwindSafe,
+    },
+    sync::RwLock,
+  },
+  windows_sys::Win32::UI::WindowsAndMessaging::{
+    PostMessageW,
+    WM_NULL,
+  },
+};
+
+type PanicHook = Box<dyn Fn(&Window, &(dyn Any + Send)) + Send + Sync>;
+
+static PANIC_HOOK: RwLock<Option<PanicHook>> = RwLock::new(None);
+
+thread_local! {
+  static PANIC: Cell<Option<Box<dyn Any + Send>>> = const { Cell::new(None) };
+}
+
+/// Calls `hook` with the window and the panic payload whenever a window procedure panics, before the
+/// panic is resumed. It runs inside the window procedure, so it can e.g. save the user's work or show a
+/// crash dialog while the rest of the app is still in one piece. Replaces the previous hook.
+pub fn set_procedure_panic_hook(hook: impl Fn(&Window, &(dyn Any + Send)) + Send + Sync + 'static) {
+  *PANIC_HOOK.write().unwrap() = Some(Box::new(hook));
+}
+
+/// Takes the panic caught in a window procedure on this thread, if there was one. Message loops that don't
+/// use [`MessageLoop::run`](crate::user::MessageLoop::run) can pass it to [`std::panic::resume_unwind`]
+/// after dispatching each message.
+pub fn take_procedure_panic() -> Option<Box<dyn Any + Send>> {
+  PANIC.take()
+}
+
+/// The message a panic payload carries, if it was made by `panic!` or one of its relatives.
+pub fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+  payload.downcast_ref::<&str>().copied().or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+}
+
+/// Runs `f`, or returns `None` if it panics. Only the first panic is kept until it is taken, since the
+/// ones after it are likely caused by it.
+pub(crate) fn catch_procedure_panic<R>(window: &Window, f: impl FnOnce() -> R) -> Option<R> {
+  let payload = match panic::catch_unwind(AssertUnwindSafe(f)) {
+    Ok(result) => return Some(result),
+    Err(payload) => payload,
+  };
+  if let Ok(hook) = PANIC_HOOK.read()
+    && let Some(hook) = hook.as_ref()
+  {
+    // A panicking hook can't be resumed any better than the panic it was called for.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(window, payload.as_ref())));
+  }
+  let previous = PANIC.take();
+  PANIC.set(previous.or(Some(payload)));
+  // Wakes up the message loop, since panics in sent messages happen while it waits for a posted one.
+  unsafe { PostMessageW(std::ptr::null_mut(), WM_NULL, 0, 0) };
+  None
+}